@@ -0,0 +1,9 @@
+-- Exchange rates for offline currency conversion. Each batch of rates shares an as_of
+-- timestamp, and the newest batch is the one in use.
+CREATE TABLE exchange_rates (
+    as_of TIMESTAMPTZ NOT NULL,
+    base TEXT NOT NULL,
+    currency TEXT NOT NULL,
+    rate DOUBLE PRECISION NOT NULL CHECK (rate > 0),
+    PRIMARY KEY (as_of, currency)
+);
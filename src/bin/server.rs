@@ -139,7 +139,27 @@ async fn run_application() -> cja::Result<()> {
         }
     });
 
-    let units = units::UnitConversion::new();
+    // Currencies convert at a locally supplied rate table: a JSON file when
+    // EXCHANGE_RATES_FILE is set, the exchange_rates table otherwise. It's reloaded hourly.
+    let exchange_rates = units::ExchangeRates::default();
+    let rate_provider: Box<dyn units::RateProvider> = match std::env::var("EXCHANGE_RATES_FILE") {
+        Ok(path) => Box::new(units::FileRateProvider::new(path)),
+        Err(_) => Box::new(units::PostgresRateProvider::new(app_state.db.clone())),
+    };
+    tokio::spawn({
+        let exchange_rates = exchange_rates.clone();
+        async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(60 * 60));
+            loop {
+                interval.tick().await;
+                if let Err(e) = exchange_rates.refresh(rate_provider.as_ref()).await {
+                    tracing::warn!(error = %e, "failed to refresh exchange rates");
+                }
+            }
+        }
+    });
+
+    let units = units::UnitConversion::with_exchange_rates(exchange_rates);
 
     println!(
         "units: {:?}",
@@ -287,58 +307,31 @@ async fn root() -> impl IntoResponse {
                             "This example server supports a handful of common unit conversions. "
                             "It's not comprehensive, but it demonstrates the MCP pattern nicely."
                         }
+                        p class="text-gray-600 mb-6" {
+                            "Units can be combined with "
+                            code { "*" } ", " code { "/" } " and " code { "^" }
+                            ", so densities, accelerations and fuel economy work too: "
+                            code { "kilograms / cubic meter" } ", "
+                            code { "feet / second^2" } ", "
+                            code { "miles / gallon" } "."
+                        }
+                        p class="text-gray-600 mb-6" {
+                            "Metric units take any SI prefix from quecto to quetta, written out or "
+                            "as a symbol: " code { "millimeters" } ", " code { "kJ" } ", "
+                            code { "µm" } ". Symbols are case-sensitive, so "
+                            code { "mW" } " is a milliwatt and " code { "MW" } " a megawatt."
+                        }
 
-                        div class="grid grid-cols-1 md:grid-cols-2 gap-6" {
-                            div class="space-y-4" {
-                                div class="bg-purple-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-purple-700" { "Length" }
-                                    p class="text-sm text-gray-600" { "meters, feet, kilometers, miles" }
-                                }
-                                div class="bg-pink-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-pink-700" { "Mass" }
-                                    p class="text-sm text-gray-600" { "kilograms, pounds, grams" }
-                                }
-                                div class="bg-purple-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-purple-700" { "Temperature" }
-                                    p class="text-sm text-gray-600" { "celsius, fahrenheit" }
-                                }
-                                div class="bg-pink-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-pink-700" { "Volume" }
-                                    p class="text-sm text-gray-600" { "liters, gallons, milliliters, cubic meters/feet/inches" }
-                                }
-                                div class="bg-purple-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-purple-700" { "Velocity" }
-                                    p class="text-sm text-gray-600" { "mph, km/h, m/s, ft/s" }
-                                }
-                                div class="bg-pink-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-pink-700" { "Area" }
-                                    p class="text-sm text-gray-600" { "square meters/feet/kilometers/miles, acres" }
-                                }
-                            }
-                            div class="space-y-4" {
-                                div class="bg-pink-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-pink-700" { "Density" }
-                                    p class="text-sm text-gray-600" { "kg/m³, lb/ft³, g/cm³, g/mL" }
-                                }
-                                div class="bg-purple-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-purple-700" { "Acceleration" }
-                                    p class="text-sm text-gray-600" { "m/s², ft/s²" }
-                                }
-                                div class="bg-pink-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-pink-700" { "Force" }
-                                    p class="text-sm text-gray-600" { "newtons, pounds force" }
-                                }
-                                div class="bg-purple-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-purple-700" { "Energy" }
-                                    p class="text-sm text-gray-600" { "joules, foot pounds" }
-                                }
-                                div class="bg-pink-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-pink-700" { "Power" }
-                                    p class="text-sm text-gray-600" { "watts, horsepower" }
-                                }
-                                div class="bg-purple-50 rounded-lg p-4 hover:shadow-md transition-shadow" {
-                                    h4 class="font-bold text-purple-700" { "Fuel Economy" }
-                                    p class="text-sm text-gray-600" { "miles/gallon, km/L, L/100km" }
+                        div class="grid grid-cols-1 md:grid-cols-2 gap-4" {
+                            @for (i, group) in units::supported_units().iter().enumerate() {
+                                @let (bg, heading) = if i % 2 == 0 {
+                                    ("bg-purple-50", "text-purple-700")
+                                } else {
+                                    ("bg-pink-50", "text-pink-700")
+                                };
+                                div class={ (bg) " rounded-lg p-4 hover:shadow-md transition-shadow" } {
+                                    h4 class={ "font-bold capitalize " (heading) } { (group.name) }
+                                    p class="text-sm text-gray-600" { (group.units.join(", ")) }
                                 }
                             }
                         }
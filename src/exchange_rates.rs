@@ -0,0 +1,138 @@
+use crate::RateTable;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+/// Why a rate table couldn't be loaded.
+#[derive(Debug)]
+pub enum RateError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Database(sqlx::Error),
+    /// The database has no rates in it yet
+    Empty,
+    /// The newest batch of rates is quoted against more than one base currency
+    MixedBases {
+        base: String,
+        other: String,
+    },
+}
+
+impl std::fmt::Display for RateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RateError::Io(e) => write!(f, "Error: Couldn't read the exchange rate file: {e}"),
+            RateError::Json(e) => write!(f, "Error: Invalid exchange rate file: {e}"),
+            RateError::Database(e) => write!(f, "Error: Couldn't load exchange rates: {e}"),
+            RateError::Empty => write!(f, "Error: No exchange rates have been stored"),
+            RateError::MixedBases { base, other } => write!(
+                f,
+                "Error: The latest exchange rates are quoted against both {base} and {other}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RateError {}
+
+/// Somewhere to load the latest exchange rates from. Nothing here goes out to a live
+/// API; rates are supplied locally and refreshed on a schedule.
+#[async_trait]
+pub trait RateProvider: Send + Sync {
+    async fn latest(&self) -> Result<RateTable, RateError>;
+}
+
+/// A fixed table is its own provider, for tests and fixtures.
+#[async_trait]
+impl RateProvider for RateTable {
+    async fn latest(&self) -> Result<RateTable, RateError> {
+        Ok(self.clone())
+    }
+}
+
+/// Reads rates from a JSON file in the format [`RateTable::from_json`] takes, re-reading
+/// it on every refresh so it can be replaced while the server runs.
+pub struct FileRateProvider {
+    path: PathBuf,
+}
+
+impl FileRateProvider {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl RateProvider for FileRateProvider {
+    async fn latest(&self) -> Result<RateTable, RateError> {
+        let json = tokio::fs::read_to_string(&self.path)
+            .await
+            .map_err(RateError::Io)?;
+        RateTable::from_json(&json).map_err(RateError::Json)
+    }
+}
+
+/// Reads the most recent set of rates from the `exchange_rates` table. Every rate in the
+/// set has to be quoted against the same base currency.
+pub struct PostgresRateProvider {
+    db: sqlx::PgPool,
+}
+
+impl PostgresRateProvider {
+    pub fn new(db: sqlx::PgPool) -> Self {
+        Self { db }
+    }
+}
+
+#[async_trait]
+impl RateProvider for PostgresRateProvider {
+    async fn latest(&self) -> Result<RateTable, RateError> {
+        use sqlx::Row;
+
+        let rows = sqlx::query(
+            "SELECT base, currency, rate, as_of FROM exchange_rates
+             WHERE as_of = (SELECT max(as_of) FROM exchange_rates)",
+        )
+        .fetch_all(&self.db)
+        .await
+        .map_err(RateError::Database)?;
+
+        let first = rows.first().ok_or(RateError::Empty)?;
+        let mut table = RateTable::new(
+            first.try_get("base").map_err(RateError::Database)?,
+            first.try_get("as_of").map_err(RateError::Database)?,
+        );
+        for row in &rows {
+            let base: &str = row.try_get("base").map_err(RateError::Database)?;
+            if !base.eq_ignore_ascii_case(&table.base) {
+                return Err(RateError::MixedBases {
+                    base: table.base,
+                    other: base.to_ascii_uppercase(),
+                });
+            }
+            table = table.with_rate(
+                row.try_get("currency").map_err(RateError::Database)?,
+                row.try_get("rate").map_err(RateError::Database)?,
+            );
+        }
+        Ok(table)
+    }
+}
+
+/// The rate table currently in use, shared between the refresh task and the MCP tool.
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeRates(Arc<RwLock<Option<Arc<RateTable>>>>);
+
+impl ExchangeRates {
+    pub fn current(&self) -> Option<Arc<RateTable>> {
+        self.0.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Loads the latest rates from `provider`. On failure the previous table stays in
+    /// use, and conversions report it as stale once it gets too old.
+    pub async fn refresh(&self, provider: &dyn RateProvider) -> Result<(), RateError> {
+        let table = provider.latest().await?;
+        *self.0.write().unwrap_or_else(|e| e.into_inner()) = Some(Arc::new(table));
+        Ok(())
+    }
+}
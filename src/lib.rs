@@ -1,7 +1,16 @@
 mod unit_conversion;
 
 pub use unit_conversion::ConversionError;
-pub use unit_conversion::convert_units;
+pub use unit_conversion::{
+    ConversionOptions, Notation, Precision, RoundingMode, VolumeDialect, convert_units,
+    convert_units_with_options,
+};
+pub use unit_conversion::{RateTable, UnitGroup, supported_units};
+
+mod exchange_rates;
+pub use exchange_rates::{
+    ExchangeRates, FileRateProvider, PostgresRateProvider, RateError, RateProvider,
+};
 
 mod mcp;
 pub use mcp::UnitConversion;
@@ -8,12 +8,19 @@ use rmcp::{
 #[derive(Clone)]
 pub struct UnitConversion {
     pub tool_router: ToolRouter<UnitConversion>,
+    exchange_rates: crate::ExchangeRates,
 }
 
 impl UnitConversion {
     pub fn new() -> Self {
+        Self::with_exchange_rates(crate::ExchangeRates::default())
+    }
+
+    /// A server that converts currencies at whatever `exchange_rates` currently holds.
+    pub fn with_exchange_rates(exchange_rates: crate::ExchangeRates) -> Self {
         Self {
             tool_router: Self::tool_router(),
+            exchange_rates,
         }
     }
 }
@@ -34,10 +41,64 @@ impl UnitConversion {
         Parameters(ConversionRequest {
             input_value,
             output_unit,
+            ambient_pressure,
+            volume_dialect,
+            dpi,
+            font_size,
+            part_decimals,
+            significant_figures,
+            decimals,
+            rounding,
+            notation,
         }): Parameters<ConversionRequest>,
     ) -> Result<CallToolResult, McpError> {
-        let result = crate::convert_units(&input_value, &output_unit)
-            .map_err(|e| McpError::new(ErrorCode::INVALID_REQUEST, e.to_string(), None))?;
+        let invalid = |e: crate::ConversionError| {
+            McpError::new(ErrorCode::INVALID_REQUEST, e.to_string(), None)
+        };
+
+        let mut options = crate::ConversionOptions {
+            exchange_rates: self.exchange_rates.current(),
+            ..Default::default()
+        };
+        if let Some(ambient_pressure) = ambient_pressure {
+            options = options
+                .with_ambient_pressure(&ambient_pressure)
+                .map_err(invalid)?;
+        }
+        if let Some(volume_dialect) = volume_dialect {
+            options = options
+                .with_volume_dialect(&volume_dialect)
+                .map_err(invalid)?;
+        }
+        if let Some(dpi) = dpi {
+            options = options.with_dpi(&dpi).map_err(invalid)?;
+        }
+        if let Some(font_size) = font_size {
+            options = options.with_font_size(&font_size).map_err(invalid)?;
+        }
+        if let Some(part_decimals) = part_decimals {
+            options = options.with_part_decimals(part_decimals).map_err(invalid)?;
+        }
+        if let Some(significant_figures) = significant_figures {
+            options = options
+                .with_significant_figures(significant_figures)
+                .map_err(invalid)?;
+        }
+        // Decimal places win if both are given
+        if let Some(decimals) = decimals {
+            options = options.with_decimals(decimals).map_err(invalid)?;
+        }
+        if let Some(rounding) = rounding {
+            options = options.with_rounding(&rounding).map_err(invalid)?;
+        }
+        if let Some(notation) = notation {
+            options = options.with_notation(&notation).map_err(invalid)?;
+        }
+
+        // Without an output unit, pick a readable one
+        let output_unit = output_unit.as_deref().unwrap_or("auto");
+        let result = crate::convert_units_with_options(&input_value, output_unit, &options)
+            .map_err(invalid)?;
 
         Ok(CallToolResult::success(vec![Content::text(result)]))
     }
@@ -65,6 +126,44 @@ impl ServerHandler for UnitConversion {
 pub struct ConversionRequest {
     #[schemars(description = "the input value")]
     pub input_value: String,
-    #[schemars(description = "the output unit")]
-    pub output_unit: String,
+    #[schemars(
+        description = "the output unit, or \"auto\", \"auto metric\" or \"auto imperial\" to pick a readable one; defaults to auto"
+    )]
+    pub output_unit: Option<String>,
+    #[schemars(
+        description = "the ambient pressure that gauge units like psig are relative to, e.g. \"14.2 psi\"; defaults to one standard atmosphere"
+    )]
+    pub ambient_pressure: Option<String>,
+    #[schemars(
+        description = "what plain cups, pints and gallons mean: \"us customary\", \"us legal\", \"imperial\" or \"metric\"; defaults to us customary"
+    )]
+    pub volume_dialect: Option<String>,
+    #[schemars(
+        description = "the resolution pixels are measured at, e.g. \"300 dpi\"; defaults to 96, the CSS reference pixel"
+    )]
+    pub dpi: Option<String>,
+    #[schemars(
+        description = "the base font size ems and rems are measured by, e.g. \"16px\"; ems don't convert without one"
+    )]
+    pub font_size: Option<String>,
+    #[schemars(
+        description = "for a mixed-unit output like \"ft+in\", how many decimal places the smallest part is rounded to, up to 9; defaults to 2"
+    )]
+    pub part_decimals: Option<usize>,
+    #[schemars(
+        description = "how many significant figures to show the result with, from 1 to 17; defaults to six, or two decimals from 1000 up"
+    )]
+    pub significant_figures: Option<u32>,
+    #[schemars(
+        description = "how many decimal places to show the result with, up to 20, instead of significant figures"
+    )]
+    pub decimals: Option<u32>,
+    #[schemars(
+        description = "how the result is rounded: \"half-even\", \"half-up\" or \"truncate\"; defaults to half-even"
+    )]
+    pub rounding: Option<String>,
+    #[schemars(
+        description = "when the result is shown in E notation: \"standard\" (from 10^15 up), \"auto\" (also below 10^-3 and from 10^6 up) or \"scientific\" (always); defaults to standard"
+    )]
+    pub notation: Option<String>,
 }
@@ -1,25 +1,111 @@
-use std::str::FromStr;
-use uom::si::f64::*;
-use uom::si::{
-    acceleration, area, energy, force, length, mass, mass_density, power,
-    thermodynamic_temperature as temperature, velocity, volume,
-};
+mod auto;
+mod clock;
+mod currency;
+mod dimension;
+mod format;
+mod lexer;
+mod mixed;
+mod number;
+mod parser;
+mod quantity;
+mod registry;
+mod substance;
+
+use clock::ClockFormat;
+use dimension::{Base, Dimension};
+use std::sync::Arc;
+
+pub use currency::RateTable;
+pub use format::{Notation, Precision, RoundingMode};
+use quantity::Quantity;
+use registry::{PrefixFamily, Prefixes, UnitMatch};
 
 #[derive(Debug, PartialEq)]
 pub enum ConversionError {
     InvalidInputFormat,
+    ParseError {
+        position: usize,
+        message: String,
+    },
     UnknownUnit(String),
-    IncompatibleUnits { from: String, to: String },
+    /// A symbol in the wrong case that could mean more than one unit, like `MM`
+    AmbiguousUnit {
+        unit: String,
+        candidates: Vec<String>,
+    },
+    IncompatibleUnits {
+        from: String,
+        to: String,
+    },
     InvalidUnitCombination,
     UnknownCompoundUnit,
     UnitCancellationNotSupported,
+    UnknownVolumeDialect(String),
+    /// A currency was used, but no exchange rate table is loaded
+    NoExchangeRates,
+    /// The exchange rate table has no rate for this ISO 4217 code
+    MissingExchangeRate(String),
+    /// The exchange rate table is older than `ConversionOptions::max_rate_age`
+    StaleExchangeRates {
+        as_of: chrono::DateTime<chrono::Utc>,
+    },
+    /// An em or rem was used without a base font size to measure it by
+    MissingFontSize,
+    /// An `at ...` clause that is neither a DPI nor a base font size
+    InvalidContext(String),
+    /// The terms of a sum like `5 ft 11 in` measure different things
+    IncompatibleTerms {
+        first: String,
+        other: String,
+    },
+    UnknownRoundingMode(String),
+    UnknownNotation(String),
+    /// A precision setting outside the digits a result can sensibly be shown with
+    PrecisionOutOfRange {
+        setting: String,
+        min: usize,
+        max: usize,
+    },
+    /// A value or result too large to hold, like `1e400 m`
+    ValueOutOfRange,
+    /// An `auto` output for something with no units to pick from, like a currency
+    NoAutoUnit(String),
+    /// An `auto` output for a substance like "1 cup sugar", which would lose the
+    /// substance; it needs a volume or mass unit to convert through its density
+    AutoSubstance(String),
+    /// A logarithmic unit like dB used inside a compound, or scaled, where it would have
+    /// to be treated as linear
+    LogarithmicUnit(String),
+    /// Zero or a negative amount converted to a logarithmic unit like dBm, which has no
+    /// level for it
+    NonPositiveLevel(String),
+    /// A unit raised or multiplied past the largest power a dimension can hold, like
+    /// `m^127 * m`
+    ExponentOutOfRange,
 }
 
 impl std::fmt::Display for ConversionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ConversionError::InvalidInputFormat => write!(f, "Error: Invalid input format"),
+            ConversionError::ParseError { position, message } => {
+                write!(
+                    f,
+                    "Error: Invalid input format at byte {position}: {message}"
+                )
+            }
             ConversionError::UnknownUnit(unit) => write!(f, "Error: Unknown unit '{unit}'"),
+            ConversionError::AmbiguousUnit { unit, candidates } => {
+                let options = match candidates.split_last() {
+                    Some((last, [])) => last.clone(),
+                    Some((last, rest)) => format!("{} or {last}", rest.join(", ")),
+                    None => String::new(),
+                };
+                write!(
+                    f,
+                    "Error: Ambiguous unit '{unit}', symbols are case-sensitive (did you mean {options}?)"
+                )
+            }
             ConversionError::IncompatibleUnits { from, to } => {
                 write!(f, "Error: Cannot convert from {from} to {to}")
             }
@@ -30,344 +116,364 @@ impl std::fmt::Display for ConversionError {
             ConversionError::UnitCancellationNotSupported => {
                 write!(f, "Error: Unit cancellation not supported")
             }
+            ConversionError::UnknownVolumeDialect(dialect) => write!(
+                f,
+                "Error: Unknown volume dialect '{dialect}' (expected us customary, us legal, imperial or metric)"
+            ),
+            ConversionError::NoExchangeRates => {
+                write!(f, "Error: No exchange rates are loaded")
+            }
+            ConversionError::MissingExchangeRate(code) => {
+                write!(f, "Error: No exchange rate for '{code}'")
+            }
+            ConversionError::StaleExchangeRates { as_of } => write!(
+                f,
+                "Error: Exchange rates are out of date (as of {})",
+                currency::format_as_of(*as_of)
+            ),
+            ConversionError::MissingFontSize => write!(
+                f,
+                "Error: Ems need a base font size, as in '1 em in px at 16px base'"
+            ),
+            ConversionError::InvalidContext(context) => write!(
+                f,
+                "Error: Invalid context '{context}' (expected a DPI like '300 dpi' or a base font size like '16px base')"
+            ),
+            ConversionError::IncompatibleTerms { first, other } => {
+                write!(f, "Error: Cannot add {other} to {first}")
+            }
+            ConversionError::UnknownRoundingMode(mode) => write!(
+                f,
+                "Error: Unknown rounding mode '{mode}' (expected half-even, half-up or truncate)"
+            ),
+            ConversionError::UnknownNotation(notation) => write!(
+                f,
+                "Error: Unknown notation '{notation}' (expected standard, auto or scientific)"
+            ),
+            ConversionError::PrecisionOutOfRange { setting, min, max } => {
+                write!(f, "Error: {setting} must be from {min} to {max}")
+            }
+            ConversionError::ValueOutOfRange => write!(f, "Error: Value out of range"),
+            ConversionError::NoAutoUnit(kind) => {
+                write!(f, "Error: No unit to pick automatically for {kind}")
+            }
+            ConversionError::AutoSubstance(name) => write!(
+                f,
+                "Error: Can't pick a unit automatically for {name}; give a volume or mass unit"
+            ),
+            ConversionError::LogarithmicUnit(unit) => write!(
+                f,
+                "Error: '{unit}' is logarithmic and can't be multiplied, divided or combined with other units"
+            ),
+            ConversionError::NonPositiveLevel(unit) => write!(
+                f,
+                "Error: '{unit}' is logarithmic and needs a positive value to convert to"
+            ),
+            ConversionError::ExponentOutOfRange => write!(f, "Error: Unit exponent out of range"),
         }
     }
 }
 
 impl std::error::Error for ConversionError {}
 
-#[derive(Debug, PartialEq)]
-enum UnitType {
-    Length,
-    Mass,
-    Temperature,
-    Volume,
-    Velocity,
-    Area,
-    MassDensity,
-    Acceleration,
-    Force,
-    Energy,
-    Power,
-    FuelEconomy,
-}
-
-#[derive(Debug)]
-struct ParsedInput {
-    value: f64,
-    unit: String,
+/// Settings for the few conversions that depend on more than the units themselves. The
+/// defaults are what [`convert_units`] uses.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionOptions {
+    /// The atmospheric pressure, in pascals, that gauge units like `psig` read relative to
+    pub ambient_pressure: f64,
+    /// What plain kitchen measures like `cup`, `pint` and `gallon` mean. Qualified ones
+    /// like `imperial gallon` ignore this.
+    pub volume_dialect: VolumeDialect,
+    /// Substance densities in kg/m³, on top of and ahead of the built-in ones
+    pub densities: Vec<(String, f64)>,
+    /// The exchange rates currencies convert at. Without them, currencies don't convert.
+    pub exchange_rates: Option<Arc<RateTable>>,
+    /// How old the exchange rates can be before conversions refuse to use them
+    pub max_rate_age: chrono::TimeDelta,
+    /// The resolution pixels are measured at, in dots per inch
+    pub dpi: f64,
+    /// The base font size ems and rems are measured by, in meters. Without one, they
+    /// don't convert.
+    pub font_size: Option<f64>,
+    /// How many decimal places the smallest part of a mixed-unit result like
+    /// `5 ft 10.87 in` is rounded to. Only [`ConversionOptions::with_part_decimals`]
+    /// sets it, so it's always in range.
+    pub(crate) part_decimals: usize,
+    /// How many digits results are shown with
+    pub precision: Precision,
+    /// Which way results are rounded to their precision
+    pub rounding: RoundingMode,
+    /// When results switch to E notation
+    pub notation: Notation,
 }
 
-fn parse_input(input: &str) -> Result<ParsedInput, ConversionError> {
-    let trimmed = input.trim();
-
-    // Handle parentheses by removing them for now
-    let cleaned = trimmed.replace("(", "").replace(")", "");
-
-    // Check if this is a multiplication expression like "10 meters * 5 meters"
-    if cleaned.contains(" * ") {
-        return parse_multiplication_expression(&cleaned);
+impl Default for ConversionOptions {
+    fn default() -> Self {
+        Self {
+            ambient_pressure: registry::STANDARD_ATMOSPHERE,
+            volume_dialect: VolumeDialect::default(),
+            densities: Vec::new(),
+            exchange_rates: None,
+            // Long enough to cover rates published on weekdays only, over a long weekend
+            max_rate_age: chrono::TimeDelta::days(4),
+            // The CSS reference pixel
+            dpi: 96.0,
+            font_size: None,
+            part_decimals: 2,
+            precision: Precision::default(),
+            rounding: RoundingMode::default(),
+            notation: Notation::default(),
+        }
     }
+}
 
-    // Split by first space
-    let parts: Vec<&str> = cleaned.splitn(2, ' ').collect();
-    if parts.len() != 2 {
-        return Err(ConversionError::InvalidInputFormat);
+impl ConversionOptions {
+    /// Sets the ambient pressure from a reading like `14.2 psi` or `950 hPa`.
+    pub fn with_ambient_pressure(mut self, reading: &str) -> Result<Self, ConversionError> {
+        let reading = parse_input(reading, &self)?;
+        let pascals = convert_value(
+            &reading,
+            &Quantity::coherent(1.0, Dimension::PRESSURE),
+            &self,
+        )?;
+        self.ambient_pressure = pascals;
+        Ok(self)
     }
 
-    // Parse the numeric value
-    let value = f64::from_str(parts[0]).map_err(|_| ConversionError::InvalidInputFormat)?;
-
-    let unit_str = parts[1].trim();
-
-    // Check if unit string contains numbers (invalid format like "2 meters")
-    if unit_str
-        .chars()
-        .any(|c| c.is_numeric() && !unit_str.contains('/') && !unit_str.contains('^'))
-    {
-        return Err(ConversionError::InvalidInputFormat);
+    /// Adds the density of a substance, like `0.6 g/mL`, so it can be named after a
+    /// quantity as in `2 cups cornmeal`. It replaces any built-in density of that name.
+    pub fn with_density(mut self, substance: &str, density: &str) -> Result<Self, ConversionError> {
+        let density = parse_input(density, &self)?;
+        let density = convert_value(
+            &density,
+            &Quantity::coherent(1.0, Dimension::MASS_DENSITY),
+            &self,
+        )?;
+        self.densities
+            .push((substance.trim().to_lowercase(), density));
+        Ok(self)
     }
 
-    Ok(ParsedInput {
-        value,
-        unit: unit_str.to_lowercase(),
-    })
-}
-
-fn parse_multiplication_expression(input: &str) -> Result<ParsedInput, ConversionError> {
-    let parts: Vec<&str> = input.split(" * ").collect();
-
-    let mut total_value = 1.0;
-    let mut unit_parts = Vec::new();
-
-    for part in parts {
-        let part = part.trim();
-        let space_idx = part.find(' ').ok_or(ConversionError::InvalidInputFormat)?;
-        let (val_str, unit_str) = part.split_at(space_idx);
-
-        let val = f64::from_str(val_str.trim()).map_err(|_| ConversionError::InvalidInputFormat)?;
-        total_value *= val;
-
-        unit_parts.push(unit_str.trim().to_lowercase());
+    /// Sets the volume dialect from a name like `imperial` or `us legal`.
+    pub fn with_volume_dialect(mut self, dialect: &str) -> Result<Self, ConversionError> {
+        self.volume_dialect = dialect.parse()?;
+        Ok(self)
     }
 
-    // Determine the resulting unit type based on multiplication
-    let result_unit = determine_compound_unit(&unit_parts);
-
-    Ok(ParsedInput {
-        value: total_value,
-        unit: result_unit,
-    })
-}
-
-fn determine_compound_unit(units: &[String]) -> String {
-    // Simple heuristic for now - if all units are length, result is area or volume
-    let all_length = units.iter().all(|u| {
-        matches!(
-            u.as_str(),
-            "meter" | "meters" | "foot" | "feet" | "kilometer" | "kilometers" | "mile" | "miles"
-        )
-    });
-
-    if all_length {
-        match units.len() {
-            2 => {
-                // Convert to square units
-                if units.iter().any(|u| u.contains("meter")) {
-                    "square meters".to_string()
-                } else if units
-                    .iter()
-                    .any(|u| u.contains("foot") || u.contains("feet"))
-                {
-                    "square feet".to_string()
-                } else if units.iter().any(|u| u.contains("kilometer")) {
-                    "square kilometers".to_string()
-                } else if units.iter().any(|u| u.contains("mile")) {
-                    "square miles".to_string()
-                } else {
-                    "square meters".to_string()
-                }
-            }
-            3 => {
-                // Convert to cubic units
-                if units.iter().any(|u| u.contains("meter")) {
-                    "cubic meters".to_string()
-                } else if units
-                    .iter()
-                    .any(|u| u.contains("foot") || u.contains("feet"))
-                {
-                    "cubic feet".to_string()
-                } else if units.iter().any(|u| u.contains("centimeter")) {
-                    "cubic centimeters".to_string()
-                } else {
-                    "cubic meters".to_string()
-                }
-            }
-            _ => units.join(" * "),
-        }
-    } else {
-        units.join(" * ")
+    /// Sets the DPI pixels are measured at, from `300` or `300 dpi`.
+    pub fn with_dpi(mut self, dpi: &str) -> Result<Self, ConversionError> {
+        let invalid = || ConversionError::InvalidContext(dpi.trim().to_string());
+        let number = dpi.trim();
+        let number = ["dpi", "ppi"]
+            .iter()
+            .find_map(|unit| strip_suffix_ignoring_case(number, unit))
+            .unwrap_or(number);
+        self.dpi = number
+            .trim()
+            .parse()
+            .ok()
+            .filter(|dpi: &f64| dpi.is_finite() && *dpi > 0.0)
+            .ok_or_else(invalid)?;
+        Ok(self)
     }
-}
 
-fn get_unit_type(unit: &str) -> Option<UnitType> {
-    // Handle compound units with various formats
-    if unit.contains('/')
-        || unit.contains(" per ")
-        || unit.contains("mph")
-        || unit.contains("kmh")
-        || unit.contains("kph")
-    {
-        // Velocity units
-        if unit.contains("miles/hour")
-            || unit.contains("mph")
-            || unit.contains("kilometers/hour")
-            || unit.contains("kmh")
-            || unit.contains("kph")
-            || unit.contains("km/h")
-            || unit.contains("miles per hour")
-            || unit.contains("meters/second")
-            || unit.contains("m/s")
-            || unit.contains("feet/second")
-            || unit.contains("ft/s")
-        {
-            return Some(UnitType::Velocity);
-        }
-        // Density units
-        if (unit.contains("kilogram") || unit.contains("gram") || unit.contains("pound"))
-            && (unit.contains("cubic") || unit.contains("milliliter") || unit.contains("liter"))
-        {
-            return Some(UnitType::MassDensity);
-        }
-        // Acceleration
-        if unit.contains("second^2") || unit.contains("second²") {
-            return Some(UnitType::Acceleration);
-        }
-        // Fuel economy
-        if (unit.contains("miles") || unit.contains("kilometers"))
-            && (unit.contains("gallon") || unit.contains("liter"))
-        {
-            return Some(UnitType::FuelEconomy);
-        }
+    /// Sets the base font size from a length like `16px` or `12pt`. A size in pixels is
+    /// measured at the DPI already set.
+    pub fn with_font_size(mut self, size: &str) -> Result<Self, ConversionError> {
+        let size = parse_input(size, &self)?;
+        self.font_size = Some(convert_value(
+            &size,
+            &Quantity::coherent(1.0, Dimension::LENGTH),
+            &self,
+        )?);
+        Ok(self)
     }
 
-    // Area units
-    if unit.contains("square") || unit.contains("acre") {
-        return Some(UnitType::Area);
+    /// Sets how many decimal places the smallest part of a mixed-unit result is rounded
+    /// to: 1 gives `5 ft 10.9 in`, 0 gives `5 ft 11 in`. Up to 9 are allowed. Clock
+    /// outputs like `h:m:s` always go to the millisecond and ignore this.
+    pub fn with_part_decimals(mut self, decimals: usize) -> Result<Self, ConversionError> {
+        self.part_decimals = mixed::check_decimals(decimals)?;
+        Ok(self)
     }
 
-    // Volume units (cubic)
-    if unit.contains("cubic") && !unit.contains('/') {
-        return Some(UnitType::Volume);
+    /// Shows results to this many significant figures, from 1 to 17.
+    pub fn with_significant_figures(mut self, figures: u32) -> Result<Self, ConversionError> {
+        self.precision = Precision::SignificantFigures(figures).check()?;
+        Ok(self)
     }
 
-    // Force units
-    if unit.contains("newton") || unit.contains("pounds force") {
-        return Some(UnitType::Force);
+    /// Shows results to this many decimal places, up to 20.
+    pub fn with_decimals(mut self, decimals: u32) -> Result<Self, ConversionError> {
+        self.precision = Precision::Decimals(decimals).check()?;
+        Ok(self)
     }
 
-    // Energy units
-    if unit.contains("joule") || unit.contains("foot pound") {
-        return Some(UnitType::Energy);
+    /// Sets the rounding mode from a name: `half-even`, `half-up` or `truncate`.
+    pub fn with_rounding(mut self, mode: &str) -> Result<Self, ConversionError> {
+        self.rounding = mode.parse()?;
+        Ok(self)
     }
 
-    // Power units
-    if unit.contains("watt") || unit.contains("horsepower") {
-        return Some(UnitType::Power);
+    /// Sets when results switch to E notation from a name: `standard` (from 10^15 up),
+    /// `auto` (also below 10^-3 and from 10^6 up) or `scientific` (always).
+    pub fn with_notation(mut self, notation: &str) -> Result<Self, ConversionError> {
+        self.notation = notation.parse()?;
+        Ok(self)
     }
 
-    // Simple units
-    match unit {
-        "meter" | "meters" | "foot" | "feet" | "kilometer" | "kilometers" | "mile" | "miles" => {
-            Some(UnitType::Length)
+    /// Applies an `at` clause like `300 dpi` or `16px base, 300 dpi`. The DPI goes first,
+    /// so a base font size in pixels is measured at it.
+    fn with_context(self, context: &str) -> Result<Self, ConversionError> {
+        let items: Vec<&str> = context
+            .split([',', ';'])
+            .flat_map(|item| item.split(" and "))
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .collect();
+        let (sizes, dpis): (Vec<&str>, Vec<&str>) = items
+            .into_iter()
+            .partition(|item| strip_suffix_ignoring_case(item, "base").is_some());
+
+        let mut options = self;
+        for dpi in dpis {
+            options = options.with_dpi(dpi)?;
         }
-        "kilogram" | "kilograms" | "pound" | "pounds" | "gram" | "grams" => Some(UnitType::Mass),
-        "celsius" | "fahrenheit" => Some(UnitType::Temperature),
-        "liter" | "liters" | "gallon" | "gallons" | "milliliter" | "milliliters" => {
-            Some(UnitType::Volume)
+        for size in sizes {
+            let size = strip_suffix_ignoring_case(size, "base").unwrap_or(size);
+            options = options.with_font_size(size)?;
         }
-        _ => None,
+        Ok(options)
     }
 }
 
-fn format_output(value: f64, unit: &str) -> String {
-    // Handle zero special case
-    if value == 0.0 {
-        return format!("0 {}", get_plural_unit(unit, true));
-    }
-
-    // Check if value is very close to 1 (within floating point precision)
-    if (value - 1.0).abs() < 5e-6 {
-        return format!("1 {}", get_plural_unit(unit, false));
-    }
-
-    // Format with appropriate precision
-    let formatted = if value.abs() >= 1000.0 {
-        // For large values, use fixed decimal places
-        format!("{value:.2}")
-    } else if value.abs() >= 1.0 {
-        // For values >= 1, calculate decimal places needed for 6 sig figs
-        let int_digits = (value.abs().log10().floor() + 1.0) as usize;
-        let decimal_places = 6_usize.saturating_sub(int_digits);
-        format!("{value:.decimal_places$}")
-    } else if value.abs() >= 0.01 {
-        // For small values, use more decimal places
-        format!("{value:.6}")
-    } else {
-        // For very small values, use scientific notation style formatting
-        format!("{value:.6}")
-    };
-
-    // Remove trailing zeros and decimal point if not needed
-    let trimmed = formatted.trim_end_matches('0').trim_end_matches('.');
+fn strip_suffix_ignoring_case<'a>(input: &'a str, suffix: &str) -> Option<&'a str> {
+    let start = input.len().checked_sub(suffix.len())?;
+    let tail = input.get(start..)?;
+    tail.eq_ignore_ascii_case(suffix).then(|| &input[..start])
+}
 
-    // Parse the value to check if it's exactly 1
-    // Check if the value is very close to 1.0 (within floating point precision)
-    let is_singular = (value - 1.0).abs() < 1e-10 || trimmed == "1";
+/// Splits a trailing `at` clause off an input or output unit, as in `px at 300 dpi`.
+fn split_context(unit: &str) -> (&str, Option<&str>) {
+    match unit.to_ascii_lowercase().rfind(" at ") {
+        Some(at) => (&unit[..at], Some(&unit[at + 4..])),
+        None => (unit, None),
+    }
+}
 
-    format!("{} {}", trimmed, get_plural_unit(unit, !is_singular))
+/// Which system plain kitchen measures belong to. A cup is 236.6 mL in US customary
+/// units, 240 mL on US nutrition labels, 284.1 mL in imperial units and 250 mL in
+/// metric ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VolumeDialect {
+    #[default]
+    UsCustomary,
+    /// The rounded metric sizes US nutrition labels use: a 240 mL cup, a 30 mL fluid
+    /// ounce
+    UsLegal,
+    Imperial,
+    /// Metric cups and spoons, with imperial pints and gallons, as in Australia
+    Metric,
 }
 
-fn get_plural_unit(unit: &str, plural: bool) -> String {
-    // For compound units, just return as-is
-    if unit.contains('/')
-        || unit.contains(" per ")
-        || unit.contains('^')
-        || unit.contains("square")
-        || unit.contains("cubic")
-    {
-        return unit.to_string();
-    }
-
-    if plural {
-        match unit {
-            "meter" => "meters".to_string(),
-            "foot" => "feet".to_string(),
-            "kilometer" => "kilometers".to_string(),
-            "mile" => "miles".to_string(),
-            "kilogram" => "kilograms".to_string(),
-            "pound" => "pounds".to_string(),
-            "liter" => "liters".to_string(),
-            "gallon" => "gallons".to_string(),
-            "newton" => "newtons".to_string(),
-            "joule" => "joules".to_string(),
-            "watt" => "watts".to_string(),
-            _ => unit.to_string(),
-        }
-    } else {
-        match unit {
-            "meters" => "meter".to_string(),
-            "feet" => "foot".to_string(),
-            "kilometers" => "kilometer".to_string(),
-            "miles" => "mile".to_string(),
-            "kilograms" => "kilogram".to_string(),
-            "pounds" => "pound".to_string(),
-            "liters" => "liter".to_string(),
-            "gallons" => "gallon".to_string(),
-            "newtons" => "newton".to_string(),
-            "joules" => "joule".to_string(),
-            "watts" => "watt".to_string(),
-            _ => unit.to_string(),
+impl std::str::FromStr for VolumeDialect {
+    type Err = ConversionError;
+
+    fn from_str(dialect: &str) -> Result<Self, Self::Err> {
+        match dialect.trim().to_lowercase().as_str() {
+            "us" | "us customary" | "customary" => Ok(VolumeDialect::UsCustomary),
+            "us legal" | "legal" => Ok(VolumeDialect::UsLegal),
+            "imperial" | "uk" => Ok(VolumeDialect::Imperial),
+            "metric" | "australian" => Ok(VolumeDialect::Metric),
+            _ => Err(ConversionError::UnknownVolumeDialect(dialect.to_string())),
         }
     }
 }
 
-pub fn convert_units(input: &str, output_unit: &str) -> Result<String, ConversionError> {
-    // Parse input
-    let parsed = parse_input(input)?;
-
-    let output_unit_lower = output_unit.trim().to_lowercase();
-
-    // Check if units exist
-    let input_type = match get_unit_type(&parsed.unit) {
-        Some(t) => t,
-        None => {
-            // Check for specific invalid combinations
-            if parsed.unit.contains("meters / celsius") || parsed.unit.contains("feet / fahrenheit")
-            {
-                return Err(ConversionError::InvalidUnitCombination);
-            } else if parsed.unit.contains("kilograms * meters")
-                || parsed.unit.contains("pounds inches")
-            {
-                return Err(ConversionError::UnknownCompoundUnit);
-            } else if parsed.unit.contains("meter / meter") || parsed.unit.contains("foot / foot") {
-                return Err(ConversionError::UnitCancellationNotSupported);
-            }
-            return Err(ConversionError::UnknownUnit(parsed.unit));
-        }
-    };
-
-    let output_type = match get_unit_type(&output_unit_lower) {
-        Some(t) => t,
-        None => return Err(ConversionError::UnknownUnit(output_unit_lower)),
-    };
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum UnitType {
+    Length,
+    Mass,
+    Time,
+    Temperature,
+    Pressure,
+    Volume,
+    Velocity,
+    Area,
+    MassDensity,
+    Acceleration,
+    Force,
+    Energy,
+    Power,
+    FuelEconomy,
+    VolumetricFlow,
+    MassFlow,
+    DynamicViscosity,
+    KinematicViscosity,
+    Information,
+    DataRate,
+    Angle,
+    Frequency,
+    AngularVelocity,
+    Current,
+    Charge,
+    Voltage,
+    Resistance,
+    Conductance,
+    Capacitance,
+    Inductance,
+    MagneticFlux,
+    MagneticFluxDensity,
+    Currency,
+    Ratio,
+}
 
-    // Check if units are compatible
-    if input_type != output_type {
-        let type_name = |t: &UnitType| match t {
+impl UnitType {
+    const ALL: [UnitType; 34] = [
+        UnitType::Length,
+        UnitType::Mass,
+        UnitType::Time,
+        UnitType::Temperature,
+        UnitType::Pressure,
+        UnitType::Volume,
+        UnitType::Velocity,
+        UnitType::Area,
+        UnitType::MassDensity,
+        UnitType::Acceleration,
+        UnitType::Force,
+        UnitType::Energy,
+        UnitType::Power,
+        UnitType::FuelEconomy,
+        UnitType::VolumetricFlow,
+        UnitType::MassFlow,
+        UnitType::DynamicViscosity,
+        UnitType::KinematicViscosity,
+        UnitType::Information,
+        UnitType::DataRate,
+        UnitType::Angle,
+        UnitType::Frequency,
+        UnitType::AngularVelocity,
+        UnitType::Current,
+        UnitType::Charge,
+        UnitType::Voltage,
+        UnitType::Resistance,
+        UnitType::Conductance,
+        UnitType::Capacitance,
+        UnitType::Inductance,
+        UnitType::MagneticFlux,
+        UnitType::MagneticFluxDensity,
+        UnitType::Currency,
+        UnitType::Ratio,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
             UnitType::Length => "length",
             UnitType::Mass => "mass",
+            UnitType::Time => "time",
             UnitType::Temperature => "temperature",
+            UnitType::Pressure => "pressure",
             UnitType::Volume => "volume",
             UnitType::Velocity => "velocity",
             UnitType::Area => "area",
@@ -377,301 +483,435 @@ pub fn convert_units(input: &str, output_unit: &str) -> Result<String, Conversio
             UnitType::Energy => "energy",
             UnitType::Power => "power",
             UnitType::FuelEconomy => "fuel economy",
-        };
-        return Err(ConversionError::IncompatibleUnits {
-            from: type_name(&input_type).to_string(),
-            to: type_name(&output_type).to_string(),
-        });
+            UnitType::VolumetricFlow => "volumetric flow rate",
+            UnitType::MassFlow => "mass flow rate",
+            UnitType::DynamicViscosity => "dynamic viscosity",
+            UnitType::KinematicViscosity => "kinematic viscosity",
+            UnitType::Information => "information",
+            UnitType::DataRate => "data rate",
+            UnitType::Angle => "angle",
+            UnitType::Frequency => "frequency",
+            UnitType::AngularVelocity => "angular velocity",
+            UnitType::Current => "current",
+            UnitType::Charge => "electric charge",
+            UnitType::Voltage => "voltage",
+            UnitType::Resistance => "resistance",
+            UnitType::Conductance => "conductance",
+            UnitType::Capacitance => "capacitance",
+            UnitType::Inductance => "inductance",
+            UnitType::MagneticFlux => "magnetic flux",
+            UnitType::MagneticFluxDensity => "magnetic flux density",
+            UnitType::Currency => "currency",
+            UnitType::Ratio => "ratio",
+        }
     }
 
-    // Perform conversion based on type
-    let result = match input_type {
-        UnitType::Length => convert_length(parsed.value, &parsed.unit, &output_unit_lower),
-        UnitType::Mass => convert_mass(parsed.value, &parsed.unit, &output_unit_lower),
-        UnitType::Temperature => {
-            convert_temperature(parsed.value, &parsed.unit, &output_unit_lower)
-        }
-        UnitType::Volume => convert_volume(parsed.value, &parsed.unit, &output_unit_lower),
-        UnitType::Velocity => convert_velocity(parsed.value, &parsed.unit, &output_unit_lower),
-        UnitType::Area => convert_area(parsed.value, &parsed.unit, &output_unit_lower),
-        UnitType::MassDensity => {
-            convert_mass_density(parsed.value, &parsed.unit, &output_unit_lower)
-        }
-        UnitType::Acceleration => {
-            convert_acceleration(parsed.value, &parsed.unit, &output_unit_lower)
-        }
-        UnitType::Force => convert_force(parsed.value, &parsed.unit, &output_unit_lower),
-        UnitType::Energy => convert_energy(parsed.value, &parsed.unit, &output_unit_lower),
-        UnitType::Power => convert_power(parsed.value, &parsed.unit, &output_unit_lower),
-        UnitType::FuelEconomy => {
-            convert_fuel_economy(parsed.value, &parsed.unit, &output_unit_lower)
+    fn dimension(self) -> Dimension {
+        match self {
+            UnitType::Length => Dimension::LENGTH,
+            UnitType::Mass => Dimension::MASS,
+            UnitType::Time => Dimension::TIME,
+            UnitType::Temperature => Dimension::TEMPERATURE,
+            UnitType::Pressure => Dimension::PRESSURE,
+            UnitType::Volume => Dimension::VOLUME,
+            UnitType::Velocity => Dimension::VELOCITY,
+            UnitType::Area => Dimension::AREA,
+            UnitType::MassDensity => Dimension::MASS_DENSITY,
+            UnitType::Acceleration => Dimension::ACCELERATION,
+            UnitType::Force => Dimension::FORCE,
+            UnitType::Energy => Dimension::ENERGY,
+            UnitType::Power => Dimension::POWER,
+            UnitType::FuelEconomy => Dimension::FUEL_ECONOMY,
+            UnitType::VolumetricFlow => Dimension::VOLUMETRIC_FLOW,
+            UnitType::MassFlow => Dimension::MASS_FLOW,
+            UnitType::DynamicViscosity => Dimension::DYNAMIC_VISCOSITY,
+            UnitType::KinematicViscosity => Dimension::KINEMATIC_VISCOSITY,
+            UnitType::Information => Dimension::INFORMATION,
+            UnitType::DataRate => Dimension::DATA_RATE,
+            UnitType::Angle => Dimension::ANGLE,
+            UnitType::Frequency => Dimension::FREQUENCY,
+            UnitType::AngularVelocity => Dimension::ANGULAR_VELOCITY,
+            UnitType::Current => Dimension::CURRENT,
+            UnitType::Charge => Dimension::CHARGE,
+            UnitType::Voltage => Dimension::VOLTAGE,
+            UnitType::Resistance => Dimension::RESISTANCE,
+            UnitType::Conductance => Dimension::CONDUCTANCE,
+            UnitType::Capacitance => Dimension::CAPACITANCE,
+            UnitType::Inductance => Dimension::INDUCTANCE,
+            UnitType::MagneticFlux => Dimension::MAGNETIC_FLUX,
+            UnitType::MagneticFluxDensity => Dimension::MAGNETIC_FLUX_DENSITY,
+            UnitType::Currency => Dimension::CURRENCY,
+            UnitType::Ratio => Dimension::NONE,
         }
-    };
+    }
 
-    Ok(format_output(result, &output_unit_lower))
+    fn from_dimension(dimension: Dimension) -> Option<UnitType> {
+        UnitType::ALL
+            .into_iter()
+            .find(|unit_type| unit_type.dimension() == dimension)
+    }
 }
 
-fn convert_length(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let length = match from_unit {
-        "meter" | "meters" => Length::new::<length::meter>(value),
-        "foot" | "feet" => Length::new::<length::foot>(value),
-        "kilometer" | "kilometers" => Length::new::<length::kilometer>(value),
-        "mile" | "miles" => Length::new::<length::mile>(value),
-        _ => unreachable!(),
-    };
+/// The name of what a dimension measures, like "length", for error messages.
+fn describe_dimension(dimension: Dimension) -> String {
+    UnitType::from_dimension(dimension)
+        .map_or("a compound unit", UnitType::name)
+        .to_string()
+}
 
-    match to_unit {
-        "meter" | "meters" => length.get::<length::meter>(),
-        "foot" | "feet" => length.get::<length::foot>(),
-        "kilometer" | "kilometers" => length.get::<length::kilometer>(),
-        "mile" | "miles" => length.get::<length::mile>(),
-        _ => unreachable!(),
-    }
+/// A group of supported units that share a [`UnitType`], for listing on the homepage.
+#[derive(Debug)]
+pub struct UnitGroup {
+    pub name: &'static str,
+    pub units: Vec<&'static str>,
 }
 
-fn convert_mass(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let mass = match from_unit {
-        "kilogram" | "kilograms" => Mass::new::<mass::kilogram>(value),
-        "pound" | "pounds" => Mass::new::<mass::pound>(value),
-        _ => unreachable!(),
-    };
+/// Every named unit in the registry, grouped by type in a stable order. Types with no
+/// named units of their own, like density, are reachable by combining these and are left
+/// out.
+pub fn supported_units() -> Vec<UnitGroup> {
+    UnitType::ALL
+        .iter()
+        .map(|&unit_type| UnitGroup {
+            name: unit_type.name(),
+            units: registry::UNITS
+                .iter()
+                .filter(|def| def.dimension == unit_type.dimension())
+                .map(|def| def.plural)
+                .collect(),
+        })
+        .filter(|group| !group.units.is_empty())
+        .collect()
+}
 
-    match to_unit {
-        "kilogram" | "kilograms" => mass.get::<mass::kilogram>(),
-        "pound" | "pounds" => mass.get::<mass::pound>(),
-        _ => unreachable!(),
+fn parse_input(input: &str, options: &ConversionOptions) -> Result<Quantity, ConversionError> {
+    if input.trim().is_empty() {
+        return Err(ConversionError::InvalidInputFormat);
     }
-}
 
-fn convert_temperature(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let temp = match from_unit {
-        "celsius" => ThermodynamicTemperature::new::<temperature::degree_celsius>(value),
-        "fahrenheit" => ThermodynamicTemperature::new::<temperature::degree_fahrenheit>(value),
-        _ => unreachable!(),
-    };
+    if let Some(seconds) = clock::parse(input) {
+        return Ok(Quantity::coherent(seconds, Dimension::TIME));
+    }
+
+    let expr = parser::parse(input)?;
 
-    match to_unit {
-        "celsius" => temp.get::<temperature::degree_celsius>(),
-        "fahrenheit" => temp.get::<temperature::degree_fahrenheit>(),
-        _ => unreachable!(),
+    // A quantity needs both a value and a unit; "meter" or "1" on their own aren't enough
+    if !expr.has_number() || !expr.has_unit() {
+        return Err(ConversionError::InvalidInputFormat);
     }
-}
 
-fn convert_volume(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let volume = match from_unit {
-        "liter" | "liters" => Volume::new::<volume::liter>(value),
-        "gallon" | "gallons" => Volume::new::<volume::gallon>(value),
-        "cubic meter" | "cubic meters" => Volume::new::<volume::cubic_meter>(value),
-        "cubic foot" | "cubic feet" => Volume::new::<volume::cubic_foot>(value),
-        "cubic centimeter" | "cubic centimeters" => Volume::new::<volume::cubic_centimeter>(value),
-        "cubic inch" | "cubic inches" => Volume::new::<volume::cubic_inch>(value),
-        _ => unreachable!(),
-    };
+    quantity::evaluate(&expr, options)
+}
 
-    match to_unit {
-        "liter" | "liters" => volume.get::<volume::liter>(),
-        "gallon" | "gallons" => volume.get::<volume::gallon>(),
-        "cubic meter" | "cubic meters" => volume.get::<volume::cubic_meter>(),
-        "cubic foot" | "cubic feet" => volume.get::<volume::cubic_foot>(),
-        "cubic centimeter" | "cubic centimeters" => volume.get::<volume::cubic_centimeter>(),
-        "cubic inch" | "cubic inches" => volume.get::<volume::cubic_inch>(),
-        _ => unreachable!(),
+/// Parses the target unit, and returns it alongside the spelling to echo back in the
+/// result. Names are lowercased, but symbols keep their case since `Mm` and `mm` are
+/// different units, and so do acronyms like "BTU".
+fn parse_output_unit(
+    unit: &str,
+    options: &ConversionOptions,
+) -> Result<(Quantity, String), ConversionError> {
+    let expr = parser::parse(unit)?;
+
+    let mut display = String::new();
+    let mut last = 0;
+    for span in expr.case_sensitive_spans() {
+        display.push_str(&unit[last..span.start].to_lowercase());
+        display.push_str(&unit[span.clone()]);
+        last = span.end;
     }
+    display.push_str(&unit[last..].to_lowercase());
+
+    Ok((quantity::evaluate(&expr, options)?, display))
 }
 
-fn convert_velocity(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let velocity = match from_unit {
-        "miles/hour" | "miles per hour" | "mph" => Velocity::new::<velocity::mile_per_hour>(value),
-        "kilometers/hour" | "kilometers per hour" | "kmh" | "kph" | "km/h" => {
-            Velocity::new::<velocity::kilometer_per_hour>(value)
-        }
-        "meters/second" | "meters per second" | "m/s" => {
-            Velocity::new::<velocity::meter_per_second>(value)
-        }
-        "feet/second" | "feet per second" | "ft/s" => {
-            Velocity::new::<velocity::foot_per_second>(value)
-        }
-        _ => unreachable!(),
+/// Output units like `binary bytes` or `decimal bits` that name a prefix family and
+/// leave the choice of prefix to us.
+fn parse_prefix_family(unit: &str) -> Option<(PrefixFamily, UnitMatch)> {
+    let (family, rest) = unit.split_once(' ')?;
+    let family = match family.to_lowercase().as_str() {
+        "binary" | "iec" => PrefixFamily::Binary,
+        "decimal" | "si" => PrefixFamily::Si,
+        _ => return None,
     };
+    registry::lookup(rest.trim())
+        .filter(|unit| unit.def.prefixes == Prefixes::Data && unit.prefix.is_none())
+        .map(|unit| (family, unit))
+}
 
-    match to_unit {
-        "miles/hour" | "miles per hour" | "mph" => velocity.get::<velocity::mile_per_hour>(),
-        "kilometers/hour" | "kilometers per hour" | "kmh" | "kph" | "km/h" => {
-            velocity.get::<velocity::kilometer_per_hour>()
-        }
-        "meters/second" | "meters per second" | "m/s" => {
-            velocity.get::<velocity::meter_per_second>()
-        }
-        "feet/second" | "feet per second" | "ft/s" => velocity.get::<velocity::foot_per_second>(),
-        _ => unreachable!(),
-    }
+fn format_output(value: f64, unit: &str, options: &ConversionOptions) -> String {
+    let number = format::number(value, options);
+    let plural = number != "1";
+    format!("{number} {}", get_plural_unit(unit, plural))
 }
 
-fn convert_area(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let area = match from_unit {
-        "square meter" | "square meters" => Area::new::<area::square_meter>(value),
-        "square foot" | "square feet" => Area::new::<area::square_foot>(value),
-        "square kilometer" | "square kilometers" => Area::new::<area::square_kilometer>(value),
-        "square mile" | "square miles" => Area::new::<area::square_mile>(value),
-        "acre" | "acres" => Area::new::<area::acre>(value),
-        _ => unreachable!(),
-    };
+fn get_plural_unit(unit: &str, plural: bool) -> String {
+    // Only switch between the registered singular and plural spellings; aliases like
+    // "mph", symbols like "km" and compounds like "kilograms / liter" are echoed back
+    // exactly as the caller wrote them
+    registry::lookup(unit)
+        .and_then(|unit| unit.name(plural))
+        .unwrap_or_else(|| unit.to_string())
+}
 
-    match to_unit {
-        "square meter" | "square meters" => area.get::<area::square_meter>(),
-        "square foot" | "square feet" => area.get::<area::square_foot>(),
-        "square kilometer" | "square kilometers" => area.get::<area::square_kilometer>(),
-        "square mile" | "square miles" => area.get::<area::square_mile>(),
-        "acre" | "acres" => area.get::<area::acre>(),
-        _ => unreachable!(),
-    }
+pub fn convert_units(input: &str, output_unit: &str) -> Result<String, ConversionError> {
+    convert_units_with_options(input, output_unit, &ConversionOptions::default())
 }
 
-fn convert_mass_density(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let density = match from_unit {
-        "kilograms / cubic meter" | "kilogram / cubic meter" => {
-            MassDensity::new::<mass_density::kilogram_per_cubic_meter>(value)
-        }
-        "pounds / cubic foot" | "pound / cubic foot" => {
-            MassDensity::new::<mass_density::pound_per_cubic_foot>(value)
-        }
-        "grams / cubic centimeter" | "gram / cubic centimeter" => {
-            MassDensity::new::<mass_density::gram_per_cubic_centimeter>(value)
-        }
-        "pounds / cubic inch" | "pound / cubic inch" => {
-            MassDensity::new::<mass_density::pound_per_cubic_inch>(value)
-        }
-        "gram / milliliter" | "grams / milliliter" => {
-            MassDensity::new::<mass_density::gram_per_cubic_centimeter>(value)
-        }
-        "kilograms / liter" | "kilogram / liter" =>
-        // 1 kg/L = 1000 kg/m³
-        {
-            MassDensity::new::<mass_density::kilogram_per_cubic_meter>(value * 1000.0)
+pub fn convert_units_with_options(
+    input: &str,
+    output_unit: &str,
+    options: &ConversionOptions,
+) -> Result<String, ConversionError> {
+    options.precision.check()?;
+
+    // Pixels and ems can be given their DPI and font size inline, on either side, as in
+    // "2 cm" to "px at 300 dpi"
+    let (input, input_context) = split_context(input);
+    let (output_unit, output_context) = split_context(output_unit);
+    let with_context;
+    let options = match [input_context, output_context] {
+        [None, None] => options,
+        contexts => {
+            let context = contexts
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(", ");
+            with_context = options.clone().with_context(&context)?;
+            &with_context
         }
-        _ => unreachable!(),
     };
 
-    match to_unit {
-        "kilograms / cubic meter" | "kilogram / cubic meter" => {
-            density.get::<mass_density::kilogram_per_cubic_meter>()
-        }
-        "pounds / cubic foot" | "pound / cubic foot" => {
-            density.get::<mass_density::pound_per_cubic_foot>()
-        }
-        "grams / cubic centimeter" | "gram / cubic centimeter" => {
-            density.get::<mass_density::gram_per_cubic_centimeter>()
-        }
-        "pounds / cubic inch" | "pound / cubic inch" => {
-            density.get::<mass_density::pound_per_cubic_inch>()
-        }
-        "gram / milliliter" | "grams / milliliter" => {
-            density.get::<mass_density::gram_per_cubic_centimeter>()
-        }
-        "kilograms / liter" | "kilogram / liter" =>
-        // 1 kg/L = 1000 kg/m³
-        {
-            density.get::<mass_density::kilogram_per_cubic_meter>() / 1000.0
-        }
-        _ => unreachable!(),
+    // A substance can be named after the quantity, as in "2 cups flour", so its volume
+    // and mass convert into each other. Units come first: "inches of water" is a pressure.
+    let (from, substance) = match parse_input(input, options) {
+        Ok(from) => (from, None),
+        Err(error) => match substance::split(input, options) {
+            Some((rest, name, density)) => (parse_input(rest, options)?, Some((name, density))),
+            None => return Err(error),
+        },
+    };
+
+    if let Some((family, unit)) = parse_prefix_family(output_unit.trim()) {
+        let value = convert_value(
+            &from,
+            &Quantity::coherent(unit.factor(options)?, unit.def.dimension),
+            options,
+        )?;
+        let prefix = registry::largest_prefix(unit.def.prefixes, family, value);
+        let symbol = format!(
+            "{}{}",
+            prefix.map_or("", |prefix| prefix.symbols[0]),
+            unit.def.symbols[0]
+        );
+        return Ok(format_output(
+            value / prefix.map_or(1.0, |prefix| prefix.factor),
+            &symbol,
+            options,
+        ));
     }
-}
 
-fn convert_acceleration(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let accel = match from_unit {
-        "meters / second^2" | "meter / second^2" => {
-            Acceleration::new::<acceleration::meter_per_second_squared>(value)
-        }
-        "feet / second^2" | "foot / second^2" => {
-            Acceleration::new::<acceleration::foot_per_second_squared>(value)
-        }
-        _ => unreachable!(),
-    };
+    if let Some(format) = ClockFormat::from_spec(output_unit.trim()) {
+        let seconds = convert_value(&from, &Quantity::coherent(1.0, Dimension::TIME), options)?;
+        return format.format(seconds);
+    }
 
-    match to_unit {
-        "meters / second^2" | "meter / second^2" => {
-            accel.get::<acceleration::meter_per_second_squared>()
+    if let Some(system) = auto::System::from_spec(output_unit) {
+        if let Some((name, _)) = substance {
+            return Err(ConversionError::AutoSubstance(name.to_string()));
         }
-        "feet / second^2" | "foot / second^2" => {
-            accel.get::<acceleration::foot_per_second_squared>()
+        let binary = parser::parse(input).is_ok_and(|expr| expr.has_binary_prefix());
+        return convert_auto(&from, system, binary, options);
+    }
+
+    if let Some(units) = mixed::split_spec(output_unit.trim()) {
+        return convert_mixed(&from, &units, options);
+    }
+
+    let (to, display_unit) = parse_output_unit(output_unit.trim(), options)?;
+
+    if let Some((name, density)) = substance {
+        let through = match (from.dimension, to.dimension) {
+            (Dimension::VOLUME, Dimension::MASS) => Some(density),
+            (Dimension::MASS, Dimension::VOLUME) => Some(1.0 / density),
+            _ => None,
+        };
+        if let Some(through) = through {
+            let bridged = Quantity::coherent(from.base_value(options) * through, to.dimension);
+            let result = convert_value(&bridged, &to, options)?;
+            return Ok(format!(
+                "{} ({name} at {})",
+                format_output(result, &display_unit, options),
+                format_output(density / 1000.0, "g/mL", &ConversionOptions::default())
+            ));
         }
-        _ => unreachable!(),
     }
-}
 
-fn convert_force(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let force = match from_unit {
-        "newton" | "newtons" => Force::new::<force::newton>(value),
-        "pounds force" | "pound force" => Force::new::<force::pound_force>(value),
-        _ => unreachable!(),
-    };
+    let result = convert_value(&from, &to, options)?;
 
-    match to_unit {
-        "newton" | "newtons" => force.get::<force::newton>(),
-        "pounds force" | "pound force" => force.get::<force::pound_force>(),
-        _ => unreachable!(),
+    // Money is only as good as the rates it converted at, so say when those were from
+    match &options.exchange_rates {
+        Some(rates) if to.dimension.involves(Base::Currency) => Ok(format!(
+            "{} (rates as of {})",
+            format_output(result, &display_unit, options),
+            currency::format_as_of(rates.as_of)
+        )),
+        _ => Ok(format_output(result, &display_unit, options)),
     }
 }
 
-fn convert_energy(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let energy = match from_unit {
-        "joule" | "joules" => Energy::new::<energy::joule>(value),
-        "foot pound" | "foot pounds" => Energy::new::<energy::foot_pound>(value),
-        _ => unreachable!(),
-    };
+/// Converts to whichever of the display units for the quantity's dimension puts the
+/// value in the most readable range: the largest unit it's at least one of, so 0.5 m is
+/// 50 cm and 86400 s is 1 day.
+fn convert_auto(
+    from: &Quantity,
+    system: auto::System,
+    binary: bool,
+    options: &ConversionOptions,
+) -> Result<String, ConversionError> {
+    let mut candidates = auto::candidates(from.dimension, system, binary)
+        .iter()
+        .map(|unit| parse_output_unit(unit, options))
+        .collect::<Result<Vec<_>, _>>()?;
+    candidates.sort_by(|(a, _), (b, _)| a.value.total_cmp(&b.value));
+
+    let mut best = None;
+    for (to, display) in candidates {
+        let value = convert_value(from, &to, options)?;
+        // Zero has no readable range, so it goes in the largest unit no bigger than the
+        // SI one
+        let fits = if value == 0.0 {
+            to.value <= 1.0 || to.absolute.is_some()
+        } else {
+            value.abs() >= 1.0 - 1e-9
+        };
+        if fits || best.is_none() {
+            best = Some((value, display));
+        }
+    }
 
-    match to_unit {
-        "joule" | "joules" => energy.get::<energy::joule>(),
-        "foot pound" | "foot pounds" => energy.get::<energy::foot_pound>(),
-        _ => unreachable!(),
+    match best {
+        Some((value, display)) => Ok(format_output(value, &display, options)),
+        None => Err(ConversionError::NoAutoUnit(describe_dimension(
+            from.dimension,
+        ))),
     }
 }
 
-fn convert_power(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    let power = match from_unit {
-        "watt" | "watts" => Power::new::<power::watt>(value),
-        "horsepower" => Power::new::<power::horsepower>(value),
-        _ => unreachable!(),
+/// Converts to a sum of units like `ft+in`, which all have to measure the same thing
+/// and scale plainly: a temperature or a decibel can't be split into parts.
+fn convert_mixed(
+    from: &Quantity,
+    units: &[&str],
+    options: &ConversionOptions,
+) -> Result<String, ConversionError> {
+    let parts = units
+        .iter()
+        .map(|unit| parse_output_unit(unit, options))
+        .collect::<Result<Vec<_>, _>>()?;
+    let Some((first, _)) = parts.first() else {
+        return Err(ConversionError::InvalidInputFormat);
     };
+    for (part, _) in &parts {
+        if part.absolute.is_some() || part.interval {
+            return Err(ConversionError::InvalidUnitCombination);
+        }
+        if part.dimension != first.dimension {
+            return Err(ConversionError::IncompatibleUnits {
+                from: describe_dimension(first.dimension),
+                to: describe_dimension(part.dimension),
+            });
+        }
+    }
+
+    let base = convert_value(from, &Quantity::coherent(1.0, first.dimension), options)?;
+    let parts: Vec<(f64, String)> = parts
+        .into_iter()
+        .map(|(part, display)| (part.value, display))
+        .collect();
+    Ok(mixed::format(base, &parts, options.part_decimals))
+}
 
-    match to_unit {
-        "watt" | "watts" => power.get::<power::watt>(),
-        "horsepower" => power.get::<power::horsepower>(),
-        _ => unreachable!(),
+/// `from` in units of `to`. A result too large for an `f64`, like `1e400 m` or a light
+/// year in meters to the 300th, is an error rather than an infinity.
+fn convert_value(
+    from: &Quantity,
+    to: &Quantity,
+    options: &ConversionOptions,
+) -> Result<f64, ConversionError> {
+    let value = scale_value(from, to, options)?;
+    if !value.is_finite() {
+        return Err(ConversionError::ValueOutOfRange);
     }
+    Ok(value)
 }
 
-fn convert_fuel_economy(value: f64, from_unit: &str, to_unit: &str) -> f64 {
-    // First convert everything to a common base: kilometers per liter
-    let km_per_liter = match from_unit {
-        "miles / gallon" | "miles per gallon" => {
-            // miles/gallon to km/L: 1 mpg = 0.425144 km/L
-            value * 0.425143707
-        }
-        "kilometers / liter" | "kilometers per liter" => value,
-        "liters / 100 kilometers" | "liters per 100 kilometers" => {
-            // L/100km to km/L: divide 100 by the value
-            if value == 0.0 { 0.0 } else { 100.0 / value }
+fn scale_value(
+    from: &Quantity,
+    to: &Quantity,
+    options: &ConversionOptions,
+) -> Result<f64, ConversionError> {
+    if from.dimension == to.dimension {
+        // A difference and a reading on an offset scale can't stand in for each other: a
+        // change of 10 °C is not 10 °C, and twice 10 psig is not 20 psig
+        if from.absolute.is_some() && to.interval || from.interval && to.absolute.is_some() {
+            let describe = |quantity: &Quantity| match quantity.absolute {
+                Some(def) if matches!(def.scale, registry::Scale::Gauge(_)) => {
+                    "gauge pressure".to_string()
+                }
+                Some(_) => describe_dimension(quantity.dimension),
+                None => format!("{} difference", describe_dimension(quantity.dimension)),
+            };
+            return Err(ConversionError::IncompatibleUnits {
+                from: describe(from),
+                to: describe(to),
+            });
         }
-        _ => unreachable!(),
-    };
+        let base = from.base_value(options);
+        return match to.absolute {
+            Some(def) => def.base_to_unit(base, options),
+            None => Ok(base / to.value),
+        };
+    }
+
+    // Fuel economy and fuel consumption (L/100km, an area dimensionally) are reciprocals
+    if to.dimension.powi(-1) == Ok(from.dimension)
+        && (from.dimension == Dimension::FUEL_ECONOMY || to.dimension == Dimension::FUEL_ECONOMY)
+    {
+        return Ok(if from.value == 0.0 {
+            0.0
+        } else {
+            1.0 / from.value / to.value
+        });
+    }
 
-    // Convert from km/L to target unit
-    match to_unit {
-        "miles / gallon" | "miles per gallon" => {
-            // km/L to miles/gallon: divide by 0.425144
-            km_per_liter / 0.425143707
+    // Angles only ever appear or vanish between frequency and angular velocity, where a
+    // cycle is one full turn: 1 Hz is 60 rpm and 2π rad/s. Everywhere else a radian is a
+    // real dimension, so `rad` won't quietly convert to a plain number.
+    let turn = std::f64::consts::TAU;
+    match (from.dimension, to.dimension) {
+        (Dimension::FREQUENCY, Dimension::ANGULAR_VELOCITY) => {
+            return Ok(from.value * turn / to.value);
         }
-        "kilometers / liter" | "kilometers per liter" => km_per_liter,
-        "liters per 100 kilometers" | "liters / 100 kilometers" => {
-            // km/L to L/100km: divide 100 by the value
-            if km_per_liter == 0.0 {
-                0.0
-            } else {
-                100.0 / km_per_liter
-            }
+        (Dimension::ANGULAR_VELOCITY, Dimension::FREQUENCY) => {
+            return Ok(from.value / turn / to.value);
         }
-        _ => unreachable!(),
+        _ => {}
+    }
+
+    if from.dimension.is_dimensionless() || to.dimension.is_dimensionless() {
+        return Err(ConversionError::UnitCancellationNotSupported);
+    }
+
+    match (
+        UnitType::from_dimension(from.dimension),
+        UnitType::from_dimension(to.dimension),
+    ) {
+        (Some(from_type), Some(to_type)) => Err(ConversionError::IncompatibleUnits {
+            from: from_type.name().to_string(),
+            to: to_type.name().to_string(),
+        }),
+        _ => Err(ConversionError::UnknownCompoundUnit),
     }
 }
 
@@ -830,285 +1070,1921 @@ mod tests {
     }
 
     #[test]
-    fn test_invalid_unit() {
+    fn test_grams_and_milliliters() {
         assert_eq!(
-            convert_units("1 invalid_unit", "meters")
-                .unwrap_err()
-                .to_string(),
-            "Error: Unknown unit 'invalid_unit'"
+            convert_units("500 grams", "pounds").unwrap(),
+            "1.10231 pounds"
         );
+        assert_eq!(convert_units("2 kilograms", "grams").unwrap(), "2000 grams");
         assert_eq!(
-            convert_units("1 meter", "invalid_unit")
-                .unwrap_err()
-                .to_string(),
-            "Error: Unknown unit 'invalid_unit'"
+            convert_units("250 milliliters", "liters").unwrap(),
+            "0.25 liters"
+        );
+        assert_eq!(
+            convert_units("1 gallon", "milliliters").unwrap(),
+            "3785.41 milliliters"
         );
     }
 
     #[test]
-    fn test_incompatible_units() {
+    fn test_nautical_units() {
+        assert_eq!(convert_units("1 nautical mile", "m").unwrap(), "1852 m");
+        assert_eq!(convert_units("10 nmi", "km").unwrap(), "18.52 km");
+        assert_eq!(convert_units("1 NM", "miles").unwrap(), "1.15078 miles");
+        assert_eq!(convert_units("1 cable", "m").unwrap(), "185.2 m");
+        assert_eq!(convert_units("10 cables", "nmi").unwrap(), "1 nmi");
+        assert_eq!(convert_units("1 fathom", "feet").unwrap(), "6 feet");
+        assert_eq!(convert_units("20 ftm", "m").unwrap(), "36.576 m");
+        assert_eq!(convert_units("1 knot", "km/h").unwrap(), "1.852 km/h");
+        assert_eq!(convert_units("30 kn", "mph").unwrap(), "34.5234 mph");
+        assert_eq!(convert_units("20 m/s", "knots").unwrap(), "38.8769 knots");
+        assert_eq!(convert_units("1 nmi/h", "kn").unwrap(), "1 kn");
+    }
+
+    #[test]
+    fn test_astronomical_units() {
+        assert_eq!(convert_units("1 AU", "km").unwrap(), "149597870.7 km");
         assert_eq!(
-            convert_units("1 meter", "kilograms")
-                .unwrap_err()
-                .to_string(),
-            "Error: Cannot convert from length to mass"
+            convert_units("1 au", "light-minutes").unwrap(),
+            "8.31675 light-minutes"
         );
         assert_eq!(
-            convert_units("1 celsius", "meters")
-                .unwrap_err()
-                .to_string(),
-            "Error: Cannot convert from temperature to length"
+            convert_units("1 light-second", "km").unwrap(),
+            "299792.46 km"
         );
+        assert_eq!(convert_units("1 light-year", "AU").unwrap(), "63241.08 AU");
+        assert_eq!(convert_units("1 pc", "ly").unwrap(), "3.26156 ly");
         assert_eq!(
-            convert_units("1 liter", "fahrenheit")
-                .unwrap_err()
-                .to_string(),
-            "Error: Cannot convert from volume to temperature"
+            convert_units("8 kpc", "light years").unwrap(),
+            "26092.51 light years"
+        );
+        assert_eq!(convert_units("1 AU/day", "km/s").unwrap(), "1731.46 km/s");
+        assert_eq!(
+            convert_units("4.2 light-years", "parsecs").unwrap(),
+            "1.28773 parsecs"
+        );
+        // Astronomically large values use E notation rather than a wall of digits
+        assert_eq!(convert_units("1 light-year", "m").unwrap(), "9.46073e15 m");
+        assert_eq!(convert_units("1 Mpc", "km").unwrap(), "3.08568e19 km");
+        assert_eq!(convert_units("1e16 m", "m").unwrap(), "1e16 m");
+        assert_eq!(
+            convert_units("2.5e15 meters", "meters").unwrap(),
+            "2.5e15 meters"
         );
     }
 
     #[test]
-    fn test_invalid_input_format() {
+    fn test_typographic_units() {
+        assert_eq!(convert_units("72 pt", "inches").unwrap(), "1 inch");
+        assert_eq!(convert_units("1 pica", "pt").unwrap(), "12 pt");
+        assert_eq!(convert_units("6 picas", "in").unwrap(), "1 in");
+        assert_eq!(convert_units("1440 twips", "inches").unwrap(), "1 inch");
+        assert_eq!(convert_units("1 pt", "twips").unwrap(), "20 twips");
+        // Pixels default to the CSS reference pixel, 96 to the inch
+        assert_eq!(convert_units("12 pt", "px").unwrap(), "16 px");
+        assert_eq!(convert_units("1 in", "pixels").unwrap(), "96 pixels");
+        // "pt" is the point, not the pint, which has no symbol
+        assert_eq!(
+            convert_units("1 pt", "mL").unwrap_err().to_string(),
+            "Error: Cannot convert from length to volume"
+        );
+        assert_eq!(convert_units("1 pint", "mL").unwrap(), "473.176 mL");
+    }
+
+    #[test]
+    fn test_typographic_context() {
+        assert_eq!(convert_units("2 cm", "px at 300 dpi").unwrap(), "236.22 px");
         assert_eq!(
-            convert_units("meter", "feet").unwrap_err().to_string(),
-            "Error: Invalid input format"
+            convert_units("600 px at 300 DPI", "inches").unwrap(),
+            "2 inches"
         );
+        assert_eq!(convert_units("1 em", "pt at 16px base").unwrap(), "12 pt");
         assert_eq!(
-            convert_units("1", "meters").unwrap_err().to_string(),
-            "Error: Invalid input format"
+            convert_units("1.5 rem", "px at 12pt base").unwrap(),
+            "24 px"
         );
+        // The DPI applies before the base font size, whichever order they're written in
         assert_eq!(
-            convert_units("", "meters").unwrap_err().to_string(),
-            "Error: Invalid input format"
+            convert_units("2 em", "in at 16px base, 160 dpi").unwrap(),
+            "0.2 in"
         );
         assert_eq!(
-            convert_units("1 2 meters", "feet").unwrap_err().to_string(),
-            "Error: Invalid input format"
+            convert_units("3 em at 10pt base", "px at 144 dpi").unwrap(),
+            "60 px"
         );
-    }
 
-    #[test]
-    fn test_plural_units() {
-        assert_eq!(convert_units("2 meters", "feet").unwrap(), "6.56168 feet");
-        assert_eq!(convert_units("5 feet", "meters").unwrap(), "1.524 meters");
+        let options = ConversionOptions::default()
+            .with_dpi("300 dpi")
+            .unwrap()
+            .with_font_size("32px")
+            .unwrap();
         assert_eq!(
-            convert_units("3 kilograms", "pounds").unwrap(),
-            "6.61387 pounds"
+            convert_units_with_options("1 em", "pt", &options).unwrap(),
+            "7.68 pt"
         );
-    }
 
-    #[test]
-    fn test_decimal_values() {
-        assert_eq!(convert_units("1.5 meters", "feet").unwrap(), "4.92126 feet");
         assert_eq!(
-            convert_units("2.5 kilograms", "pounds").unwrap(),
-            "5.51156 pounds"
+            convert_units("1 em", "px").unwrap_err().to_string(),
+            "Error: Ems need a base font size, as in '1 em in px at 16px base'"
         );
         assert_eq!(
-            convert_units("0.25 gallons", "liters").unwrap(),
-            "0.946353 liters"
+            convert_units("2 cm", "px at high resolution")
+                .unwrap_err()
+                .to_string(),
+            "Error: Invalid context 'high resolution' (expected a DPI like '300 dpi' or a base font size like '16px base')"
+        );
+        assert_eq!(
+            convert_units("2 cm", "px at -5 dpi")
+                .unwrap_err()
+                .to_string(),
+            "Error: Invalid context '-5 dpi' (expected a DPI like '300 dpi' or a base font size like '16px base')"
+        );
+        assert_eq!(
+            convert_units("1 em", "px at 16 kg base")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from mass to length"
         );
     }
 
     #[test]
-    fn test_negative_values() {
-        assert_eq!(convert_units("-5 meters", "feet").unwrap(), "-16.4042 feet");
+    fn test_flow_rates() {
+        assert_eq!(convert_units("10 L/min", "gpm").unwrap(), "2.64172 gpm");
+        assert_eq!(convert_units("1000 cfm", "m³/h").unwrap(), "1699.01 m³/h");
         assert_eq!(
-            convert_units("-10 celsius", "fahrenheit").unwrap(),
-            "14 fahrenheit"
+            convert_units("1 gallon per minute", "liters per hour").unwrap(),
+            "227.125 liters per hour"
         );
+        assert_eq!(convert_units("1 kg/s", "lb/h").unwrap(), "7936.64 lb/h");
         assert_eq!(
-            convert_units("-2.5 kilograms", "pounds").unwrap(),
-            "-5.51156 pounds"
+            convert_units("10 kg/s", "m³/h").unwrap_err().to_string(),
+            "Error: Cannot convert from mass flow rate to volumetric flow rate"
         );
     }
 
     #[test]
-    fn test_zero_values() {
-        assert_eq!(convert_units("0 meters", "feet").unwrap(), "0 feet");
-        assert_eq!(convert_units("0 kilograms", "pounds").unwrap(), "0 pounds");
-        assert_eq!(convert_units("0 liters", "gallons").unwrap(), "0 gallons");
+    fn test_viscosity() {
+        assert_eq!(convert_units("1 Pa·s", "cP").unwrap(), "1000 cP");
+        assert_eq!(convert_units("1 poise", "Pa*s").unwrap(), "0.1 Pa*s");
+        assert_eq!(convert_units("1 centipoise", "mPa·s").unwrap(), "1 mPa·s");
+        assert_eq!(convert_units("1 St", "cSt").unwrap(), "100 cSt");
+        assert_eq!(convert_units("1 cSt", "mm²/s").unwrap(), "1 mm²/s");
+        assert_eq!(
+            convert_units("1 cP", "cSt").unwrap_err().to_string(),
+            "Error: Cannot convert from dynamic viscosity to kinematic viscosity"
+        );
     }
 
     #[test]
-    fn test_case_insensitive() {
-        assert_eq!(convert_units("1 METER", "FEET").unwrap(), "3.28084 feet");
-        assert_eq!(convert_units("1 Meter", "Feet").unwrap(), "3.28084 feet");
+    fn test_logarithmic_units() {
+        assert_eq!(convert_units("30 dBm", "watts").unwrap(), "1 watt");
+        assert_eq!(convert_units("0.5 W", "dBm").unwrap(), "26.9897 dBm");
+        assert_eq!(convert_units("20 dB", "ratio").unwrap(), "100 ratio");
+        assert_eq!(convert_units("1000 ratio", "dB").unwrap(), "30 dB");
+        assert_eq!(convert_units("-3 dBW", "mW").unwrap(), "501.187 mW");
+        assert_eq!(convert_units("0 dBW", "dBm").unwrap(), "30 dBm");
+        assert_eq!(convert_units("1 Np", "dB").unwrap(), "8.68589 dB");
+        // Voltage levels go 20 dB to a decade, since power goes with the square
+        assert_eq!(convert_units("20 dBV", "volts").unwrap(), "10 volts");
+        assert_eq!(convert_units("0 dBu", "V").unwrap(), "0.774597 V");
+    }
+
+    #[test]
+    fn test_logarithmic_units_are_never_linear() {
+        let error = "Error: 'dBm' is logarithmic and can't be multiplied, divided or combined with other units";
+        assert_eq!(
+            convert_units("2 * 30 dBm", "W").unwrap_err().to_string(),
+            error
+        );
+        assert_eq!(
+            convert_units("30 dBm * 2 h", "J").unwrap_err().to_string(),
+            error
+        );
+        assert_eq!(
+            convert_units("1 mW/Hz", "dBm/Hz").unwrap_err().to_string(),
+            error
+        );
+        assert_eq!(
+            convert_units("30 dBm", "dBV").unwrap_err().to_string(),
+            "Error: Cannot convert from power to voltage"
+        );
+        assert_eq!(
+            convert_units("-10 W", "dBm").unwrap_err().to_string(),
+            "Error: 'dBm' is logarithmic and needs a positive value to convert to"
+        );
+        assert_eq!(
+            convert_units("0 W", "dBm").unwrap_err(),
+            ConversionError::NonPositiveLevel("dBm".to_string())
+        );
+    }
+
+    #[test]
+    fn test_kitchen_volumes() {
+        assert_eq!(convert_units("1 cup", "mL").unwrap(), "236.588 mL");
+        assert_eq!(convert_units("1 cup", "fl oz").unwrap(), "8 fl oz");
+        assert_eq!(convert_units("3 tsp", "tbsp").unwrap(), "1 tbsp");
+        assert_eq!(convert_units("2 tablespoons", "ml").unwrap(), "29.5735 ml");
+        assert_eq!(convert_units("2 pints", "quarts").unwrap(), "1 quart");
+        assert_eq!(convert_units("4 qt", "gal").unwrap(), "1 gal");
+        assert_eq!(convert_units("16 cups", "gallons").unwrap(), "1 gallon");
+        assert_eq!(convert_units("500 mL", "cups").unwrap(), "2.11338 cups");
+        // "oz" on its own is still a weight
+        assert_eq!(
+            convert_units("8 fl oz", "oz").unwrap_err().to_string(),
+            "Error: Cannot convert from volume to mass"
+        );
+    }
+
+    #[test]
+    fn test_volume_dialects() {
+        let convert = |input: &str, output: &str, dialect: &str| {
+            let options = ConversionOptions::default()
+                .with_volume_dialect(dialect)
+                .unwrap();
+            convert_units_with_options(input, output, &options).unwrap()
+        };
+        assert_eq!(convert("1 cup", "mL", "us customary"), "236.588 mL");
+        assert_eq!(convert("1 cup", "mL", "us legal"), "240 mL");
+        assert_eq!(convert("1 cup", "mL", "imperial"), "284.131 mL");
+        assert_eq!(convert("1 cup", "mL", "metric"), "250 mL");
+        assert_eq!(convert("1 pint", "mL", "us legal"), "473.176 mL");
+        assert_eq!(convert("1 pint", "fl oz", "imperial"), "20 fl oz");
+        assert_eq!(convert("1 gallon", "L", "imperial"), "4.54609 L");
+        assert_eq!(convert("1 gallon", "L", "metric"), "4.54609 L");
+        assert_eq!(convert("1 fl oz", "mL", "us legal"), "30 mL");
+        assert_eq!(convert("1 tbsp", "mL", "metric"), "15 mL");
+        // A UK car doing 40 miles to the imperial gallon
+        assert_eq!(
+            convert("40 miles/gallon", "L/100km", "imperial"),
+            "7.06202 L/100km"
+        );
+
+        // Qualified measures mean the same thing in every dialect
+        assert_eq!(
+            convert("1 imperial gallon", "L", "us customary"),
+            "4.54609 L"
+        );
+        assert_eq!(convert("1 US gallon", "L", "imperial"), "3.78541 L");
+        assert_eq!(convert("1 us fl oz", "mL", "imperial"), "29.5735 mL");
+        assert_eq!(
+            convert("1 imperial pint", "US pints", "metric"),
+            "1.20095 US pints"
+        );
+        assert_eq!(
+            convert("1 US legal cup", "metric cups", "imperial"),
+            "0.96 metric cups"
+        );
+        assert_eq!(convert("1 australian tablespoon", "tsp", "metric"), "4 tsp");
+        assert_eq!(convert("1 imperial cup", "cups", "imperial"), "1 cup");
+
+        assert_eq!(
+            ConversionOptions::default()
+                .with_volume_dialect("martian")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown volume dialect 'martian' (expected us customary, us legal, imperial or metric)"
+        );
+    }
+
+    #[test]
+    fn test_substance_densities() {
+        assert_eq!(
+            convert_units("2 cups flour", "grams").unwrap(),
+            "250.784 grams (flour at 0.53 g/mL)"
+        );
+        assert_eq!(
+            convert_units("1 liter of milk", "kg").unwrap(),
+            "1.03 kg (milk at 1.03 g/mL)"
+        );
+        assert_eq!(
+            convert_units("200 g Sugar", "cups").unwrap(),
+            "1.00041 cups (sugar at 0.845 g/mL)"
+        );
+        assert_eq!(
+            convert_units("1 cup brown sugar", "g").unwrap(),
+            "220.027 g (brown sugar at 0.93 g/mL)"
+        );
+        assert_eq!(
+            convert_units("1 tbsp honey", "oz").unwrap(),
+            "0.740655 oz (honey at 1.42 g/mL)"
+        );
+        assert_eq!(
+            convert_units("10 gallons gasoline", "lb").unwrap(),
+            "61.756 lb (gasoline at 0.74 g/mL)"
+        );
+        // Without a bridge to make, the substance changes nothing
+        assert_eq!(convert_units("2 cups flour", "mL").unwrap(), "473.176 mL");
+        // A unit that happens to end in a substance's name is still that unit
+        assert_eq!(
+            convert_units("1 inch of water", "Pa").unwrap(),
+            "249.089 Pa"
+        );
+        assert_eq!(
+            convert_units("2 cups flour", "meters")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from volume to length"
+        );
+        assert_eq!(
+            convert_units("2 cups sawdust", "g")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown unit 'sawdust'"
+        );
+
+        let options = ConversionOptions::default()
+            .with_density("sawdust", "0.21 g/cm³")
+            .unwrap()
+            .with_density("flour", "120 g/cup")
+            .unwrap();
+        assert_eq!(
+            convert_units_with_options("2 cups sawdust", "g", &options).unwrap(),
+            "99.3671 g (sawdust at 0.21 g/mL)"
+        );
+        assert_eq!(
+            convert_units_with_options("2 cups flour", "g", &options).unwrap(),
+            "240 g (flour at 0.50721 g/mL)"
+        );
+        assert_eq!(
+            ConversionOptions::default()
+                .with_density("sawdust", "0.21 g")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from mass to density"
+        );
+    }
+
+    #[test]
+    fn test_length_units() {
+        assert_eq!(convert_units("1 foot", "inches").unwrap(), "12 inches");
+        assert_eq!(
+            convert_units("100 yards", "meters").unwrap(),
+            "91.44 meters"
+        );
+        assert_eq!(
+            convert_units("1 inch", "centimeters").unwrap(),
+            "2.54 centimeters"
+        );
+        assert_eq!(convert_units("3 cm", "mm").unwrap(), "30 mm");
+        assert_eq!(convert_units("1 mm", "µm").unwrap(), "1000 µm");
+        assert_eq!(
+            convert_units("550 nm", "micrometers").unwrap(),
+            "0.55 micrometers"
+        );
+        assert_eq!(convert_units("1 micron", "um").unwrap(), "1 um");
+        assert_eq!(convert_units("1 nm", "Å").unwrap(), "10 Å");
+        assert_eq!(convert_units("1.54 angstroms", "pm").unwrap(), "154 pm");
+        assert_eq!(convert_units("1 ångström", "nm").unwrap(), "0.1 nm");
+        assert_eq!(convert_units("1000 thou", "inches").unwrap(), "1 inch");
+        assert_eq!(convert_units("4 mils", "mm").unwrap(), "0.1016 mm");
+        assert_eq!(convert_units("1 chain", "ft").unwrap(), "66 ft");
+        assert_eq!(convert_units("80 ch", "mi").unwrap(), "1 mi");
+        assert_eq!(convert_units("1 furlong", "yards").unwrap(), "220 yards");
+        assert_eq!(convert_units("8 fur", "miles").unwrap(), "1 mile");
+        assert_eq!(convert_units("1 league", "km").unwrap(), "4.82803 km");
+    }
+
+    #[test]
+    fn test_mass_units() {
+        assert_eq!(convert_units("1 pound", "ounces").unwrap(), "16 ounces");
+        assert_eq!(convert_units("1 oz", "dr").unwrap(), "16 dr");
+        assert_eq!(convert_units("11 stone", "kg").unwrap(), "69.8532 kg");
+        assert_eq!(convert_units("1 st", "lb").unwrap(), "14 lb");
+        assert_eq!(convert_units("7000 grains", "pounds").unwrap(), "1 pound");
+        assert_eq!(convert_units("1 gr", "mg").unwrap(), "64.7989 mg");
+        assert_eq!(convert_units("1 carat", "mg").unwrap(), "200 mg");
+        assert_eq!(convert_units("5 ct", "g").unwrap(), "1 g");
+        assert_eq!(convert_units("1 ton", "lb").unwrap(), "2000 lb");
+        assert_eq!(convert_units("1 short ton", "kg").unwrap(), "907.185 kg");
+        assert_eq!(convert_units("1 long ton", "lb").unwrap(), "2240 lb");
+        assert_eq!(convert_units("1 metric ton", "kg").unwrap(), "1000 kg");
+        assert_eq!(convert_units("2.5 t", "tonnes").unwrap(), "2.5 tonnes");
+        assert_eq!(convert_units("1 Gt", "Mt").unwrap(), "1000 Mt");
+        assert_eq!(
+            convert_units("1 tonne", "short tons").unwrap(),
+            "1.10231 short tons"
+        );
+    }
+
+    #[test]
+    fn test_troy_and_apothecary_weights() {
+        assert_eq!(convert_units("1 troy ounce", "g").unwrap(), "31.1035 g");
+        assert_eq!(convert_units("1 ozt", "oz").unwrap(), "1.09714 oz");
+        assert_eq!(convert_units("10 oz t", "grains").unwrap(), "4800 grains");
+        assert_eq!(convert_units("1 troy pound", "ozt").unwrap(), "12 ozt");
+        assert_eq!(convert_units("1 lb t", "lb").unwrap(), "0.822857 lb");
+        assert_eq!(
+            convert_units("20 dwt", "troy ounces").unwrap(),
+            "1 troy ounce"
+        );
+        assert_eq!(convert_units("1 scruple", "grains").unwrap(), "20 grains");
+        assert_eq!(convert_units("3 ℈", "ʒ").unwrap(), "1 ʒ");
+        assert_eq!(convert_units("8 drachms", "℥").unwrap(), "1 ℥");
+        assert_eq!(
+            convert_units("1 apothecary ounce", "troy ounces").unwrap(),
+            "1 troy ounce"
+        );
+        assert_eq!(
+            convert_units("1 apothecary pound", "g").unwrap(),
+            "373.242 g"
+        );
+    }
+
+    #[test]
+    fn test_registry_names_are_unique() {
+        for def in registry::UNITS {
+            let spellings = [def.name, def.plural].into_iter();
+            for name in spellings.chain(
+                def.aliases
+                    .iter()
+                    .copied()
+                    .chain(def.symbols.iter().copied()),
+            ) {
+                let unit = registry::lookup(name).unwrap();
+                assert!(
+                    std::ptr::eq(unit.def, def) && unit.prefix.is_none(),
+                    "'{name}' resolves to a different unit than {}",
+                    def.name
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_unit() {
+        assert_eq!(
+            convert_units("1 invalid_unit", "meters")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown unit 'invalid_unit'"
+        );
+        assert_eq!(
+            convert_units("1 meter", "invalid_unit")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown unit 'invalid_unit'"
+        );
+    }
+
+    #[test]
+    fn test_incompatible_units() {
+        assert_eq!(
+            convert_units("1 meter", "kilograms")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from length to mass"
+        );
+        assert_eq!(
+            convert_units("1 celsius", "meters")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from temperature to length"
+        );
+        assert_eq!(
+            convert_units("1 liter", "fahrenheit")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from volume to temperature"
+        );
+    }
+
+    #[test]
+    fn test_invalid_input_format() {
+        assert_eq!(
+            convert_units("meter", "feet").unwrap_err().to_string(),
+            "Error: Invalid input format"
+        );
+        assert_eq!(
+            convert_units("1", "meters").unwrap_err().to_string(),
+            "Error: Invalid input format"
+        );
+        assert_eq!(
+            convert_units("", "meters").unwrap_err().to_string(),
+            "Error: Invalid input format"
+        );
+        assert_eq!(
+            convert_units("1 2 meters", "feet").unwrap_err().to_string(),
+            "Error: Invalid input format at byte 2: expected a unit or operator"
+        );
+    }
+
+    #[test]
+    fn test_plural_units() {
+        assert_eq!(convert_units("2 meters", "feet").unwrap(), "6.56168 feet");
+        assert_eq!(convert_units("5 feet", "meters").unwrap(), "1.524 meters");
+        assert_eq!(
+            convert_units("3 kilograms", "pounds").unwrap(),
+            "6.61387 pounds"
+        );
+    }
+
+    #[test]
+    fn test_decimal_values() {
+        assert_eq!(convert_units("1.5 meters", "feet").unwrap(), "4.92126 feet");
+        assert_eq!(
+            convert_units("2.5 kilograms", "pounds").unwrap(),
+            "5.51156 pounds"
+        );
+        assert_eq!(
+            convert_units("0.25 gallons", "liters").unwrap(),
+            "0.946353 liters"
+        );
+    }
+
+    #[test]
+    fn test_thousands_separators() {
+        assert_eq!(convert_units("1,500 ft", "m").unwrap(), "457.2 m");
+        assert_eq!(convert_units("1,234,567.5 m", "km").unwrap(), "1234.57 km");
+        // A comma that doesn't separate thousands isn't guessed at as a decimal comma
+        assert_eq!(
+            convert_units("1,5 m", "ft").unwrap_err().to_string(),
+            "Error: Invalid input format at byte 1: unexpected character ','"
+        );
+        assert_eq!(
+            convert_units("1500,00 m", "ft").unwrap_err().to_string(),
+            "Error: Invalid input format at byte 4: unexpected character ','"
+        );
+    }
+
+    #[test]
+    fn test_underscore_separators() {
+        assert_eq!(convert_units("1_000_000 B", "MB").unwrap(), "1 MB");
+        assert_eq!(convert_units("2_500.5 m", "km").unwrap(), "2.5005 km");
+    }
+
+    #[test]
+    fn test_fractions() {
+        assert_eq!(convert_units("3/4 cup", "tbsp").unwrap(), "12 tbsp");
+        assert_eq!(convert_units("1/2 mile", "feet").unwrap(), "2640 feet");
+        // With spaces around it, the slash is still a division
+        assert_eq!(
+            convert_units("60 miles / 2 hours", "mph").unwrap(),
+            "30 mph"
+        );
+    }
+
+    #[test]
+    fn test_mixed_numbers() {
+        assert_eq!(convert_units("2 1/2 inches", "cm").unwrap(), "6.35 cm");
+        assert_eq!(convert_units("1 3/4 cups", "tbsp").unwrap(), "28 tbsp");
+        assert_eq!(convert_units("-2 1/2 ft", "in").unwrap(), "-30 in");
+    }
+
+    #[test]
+    fn test_vulgar_fractions() {
+        assert_eq!(convert_units("½ mile", "feet").unwrap(), "2640 feet");
+        assert_eq!(convert_units("1½ cups", "tbsp").unwrap(), "24 tbsp");
+        assert_eq!(convert_units("2 ¾ in", "mm").unwrap(), "69.85 mm");
+        assert_eq!(convert_units("⅛ in", "mm").unwrap(), "3.175 mm");
+    }
+
+    #[test]
+    fn test_times_ten_notation() {
+        assert_eq!(convert_units("1.5×10^3 m", "km").unwrap(), "1.5 km");
+        assert_eq!(convert_units("2 x 10^-3 kg", "g").unwrap(), "2 g");
+        assert_eq!(convert_units("3×10⁸ m/s", "km/s").unwrap(), "300000 km/s");
+        assert_eq!(convert_units("5 × 10⁻⁶ m", "µm").unwrap(), "5 µm");
+    }
+
+    #[test]
+    fn test_e_notation() {
+        assert_eq!(convert_units("1e3 m", "km").unwrap(), "1 km");
+        assert_eq!(convert_units("1.5E-3 km", "m").unwrap(), "1.5 m");
+        assert_eq!(convert_units("2.5e+2 g", "kg").unwrap(), "0.25 kg");
+        // Without digits after it, the e starts a word
+        assert_eq!(convert_units("2em at 16px base", "px").unwrap(), "32 px");
+        assert_eq!(
+            convert_units("1.2.3 m", "ft").unwrap_err().to_string(),
+            "Error: Invalid input format at byte 0: invalid number '1.2.3'"
+        );
+    }
+
+    #[test]
+    fn test_programmer_literals() {
+        assert_eq!(convert_units("0xFF B", "bits").unwrap(), "2040 bits");
+        assert_eq!(convert_units("0x1_0000 B", "KiB").unwrap(), "64 KiB");
+        assert_eq!(convert_units("0b1010 m", "m").unwrap(), "10 m");
+        assert_eq!(convert_units("0o17 ft", "in").unwrap(), "180 in");
+        // A zero before a unit is still a zero
+        assert_eq!(convert_units("0B", "bits").unwrap(), "0 bits");
+    }
+
+    #[test]
+    fn test_multi_part_quantities() {
+        assert_eq!(convert_units("5 ft 11 in", "cm").unwrap(), "180.34 cm");
+        assert_eq!(convert_units("6' 2\"", "in").unwrap(), "74 in");
+        assert_eq!(convert_units("5'11\"", "m").unwrap(), "1.8034 m");
+        assert_eq!(convert_units("1 h 30 min", "min").unwrap(), "90 min");
+        assert_eq!(convert_units("2 st 7 lb", "kg").unwrap(), "15.8757 kg");
+        assert_eq!(convert_units("3 lb 4 oz", "oz").unwrap(), "52 oz");
+        assert_eq!(
+            convert_units("1 mile 1 1/2 furlongs", "yards").unwrap(),
+            "2090 yards"
+        );
+        // A leading minus covers the whole sum
+        assert_eq!(convert_units("-1 h 30 min", "min").unwrap(), "-90 min");
+        // A sum binds tighter than division
+        assert_eq!(
+            convert_units("60 miles / 1 h 30 min", "mph").unwrap(),
+            "40 mph"
+        );
+    }
+
+    #[test]
+    fn test_multi_part_quantities_must_match() {
+        assert_eq!(
+            convert_units("5 ft 3 kg", "m").unwrap_err().to_string(),
+            "Error: Cannot add mass to length"
+        );
+        assert_eq!(
+            convert_units("1 h 30 km", "min").unwrap_err().to_string(),
+            "Error: Cannot add length to time"
+        );
+        assert_eq!(
+            convert_units("5 ft 11", "cm").unwrap_err().to_string(),
+            "Error: Invalid input format at byte 7: expected a unit"
+        );
+    }
+
+    #[test]
+    fn test_mixed_unit_output() {
+        assert_eq!(convert_units("1.8 m", "ft+in").unwrap(), "5 ft 10.87 in");
+        assert_eq!(convert_units("72 kg", "st+lb").unwrap(), "11 st 4.73 lb");
+        assert_eq!(
+            convert_units("8130 s", "h+min+s").unwrap(),
+            "2 h 15 min 30 s"
+        );
+        assert_eq!(
+            convert_units("1.8 m", "feet + inches").unwrap(),
+            "5 feet 10.87 inches"
+        );
+        // Largest first, whatever order the spec is in
+        assert_eq!(convert_units("100 oz", "oz+lb").unwrap(), "6 lb 4 oz");
+        assert_eq!(convert_units("1 mi", "yd+ft+in").unwrap(), "1760 yd");
+        assert_eq!(convert_units("-1.8 m", "ft+in").unwrap(), "-5 ft 10.87 in");
+        assert_eq!(convert_units("0 m", "ft+in").unwrap(), "0 in");
+    }
+
+    #[test]
+    fn test_auto_output() {
+        assert_eq!(convert_units("0.000003 m", "auto").unwrap(), "3 µm");
+        assert_eq!(convert_units("86400 s", "auto").unwrap(), "1 day");
+        assert_eq!(convert_units("90 s", "auto").unwrap(), "1.5 min");
+        assert_eq!(convert_units("0.5 m", "auto").unwrap(), "50 cm");
+        assert_eq!(convert_units("5280 ft", "auto").unwrap(), "1.60934 km");
+        assert_eq!(convert_units("2500 g", "auto").unwrap(), "2.5 kg");
+        assert_eq!(convert_units("1500000000 B", "auto").unwrap(), "1.5 GB");
+        assert_eq!(convert_units("0.047 A", "auto").unwrap(), "47 mA");
+        assert_eq!(convert_units("30 dBm", "auto").unwrap(), "1 W");
+        assert_eq!(convert_units("0 m", "auto").unwrap(), "0 m");
+        assert_eq!(convert_units("300 K", "auto").unwrap(), "26.85 °C");
+        assert_eq!(convert_units("25000 cm²", "auto").unwrap(), "2.5 m²");
+        assert_eq!(convert_units("9.81 N/kg", "auto").unwrap(), "9.81 m/s²");
+        // Binary prefixes in, binary prefixes out
+        assert_eq!(convert_units("1.5 GiB", "auto").unwrap(), "1.5 GiB");
+        assert_eq!(convert_units("2048 KiB", "auto").unwrap(), "2 MiB");
+        assert_eq!(
+            convert_units("1 cup sugar", "auto")
+                .unwrap_err()
+                .to_string(),
+            "Error: Can't pick a unit automatically for sugar; give a volume or mass unit"
+        );
+    }
+
+    #[test]
+    fn test_auto_output_systems() {
+        assert_eq!(
+            convert_units("1.609344 km", "auto imperial").unwrap(),
+            "1 mi"
+        );
+        assert_eq!(
+            convert_units("30 cm", "auto imperial").unwrap(),
+            "11.811 in"
+        );
+        assert_eq!(
+            convert_units("1 kg", "Auto Imperial").unwrap(),
+            "2.20462 lb"
+        );
+        assert_eq!(convert_units("300 K", "auto imperial").unwrap(), "80.33 °F");
+        // Imperial falls back to metric where it has no units of its own
+        assert_eq!(convert_units("7200 s", "auto imperial").unwrap(), "2 h");
+        assert_eq!(convert_units("3 mi", "auto metric").unwrap(), "4.82803 km");
+        // Compounds with no unit of their own are spelled in the system's base units
+        assert_eq!(
+            convert_units("5 kg/s", "auto imperial").unwrap(),
+            "11.0231 lb/s"
+        );
+        assert_eq!(
+            convert_units("1000 kg/m³", "auto imperial").unwrap(),
+            "62.428 lb/ft³"
+        );
+
+        let options = with_rates(chrono::Utc::now());
+        assert_eq!(
+            convert_units_with_options("5 USD", "auto", &options)
+                .unwrap_err()
+                .to_string(),
+            "Error: No unit to pick automatically for currency"
+        );
+    }
+
+    #[test]
+    fn test_mixed_unit_rounding() {
+        let options = ConversionOptions::default().with_part_decimals(1).unwrap();
+        assert_eq!(
+            convert_units_with_options("1.8 m", "ft+in", &options).unwrap(),
+            "5 ft 10.9 in"
+        );
+        let options = ConversionOptions::default().with_part_decimals(0).unwrap();
+        assert_eq!(
+            convert_units_with_options("1.8 m", "ft+in", &options).unwrap(),
+            "5 ft 11 in"
+        );
+        // Rounding happens before the split, so the inches never reach twelve
+        assert_eq!(
+            convert_units_with_options("5.999 ft", "ft+in", &options).unwrap(),
+            "6 ft"
+        );
+        assert_eq!(convert_units("5 ft 11.999 in", "ft+in").unwrap(), "6 ft");
+        assert_eq!(
+            convert_units("1 h 59 min 59.999 s", "h+min+s").unwrap(),
+            "2 h"
+        );
+
+        assert_eq!(
+            convert_units("1.8 m", "ft+kg").unwrap_err().to_string(),
+            "Error: Cannot convert from length to mass"
+        );
+        assert_eq!(
+            convert_units("1.8 m", "ft+").unwrap_err().to_string(),
+            "Error: Invalid input format at byte 0: unexpected end of input"
+        );
+        assert_eq!(
+            convert_units("300 K", "°C+K").unwrap_err().to_string(),
+            "Error: Invalid unit combination"
+        );
+
+        let options = ConversionOptions::default().with_part_decimals(9).unwrap();
+        assert_eq!(
+            convert_units_with_options("1.8 m", "ft+in", &options).unwrap(),
+            "5 ft 10.866141732 in"
+        );
+        assert_eq!(
+            ConversionOptions::default()
+                .with_part_decimals(1_000_000)
+                .unwrap_err()
+                .to_string(),
+            "Error: part decimals must be from 0 to 9"
+        );
+        // Clock outputs keep their milliseconds
+        let options = ConversionOptions::default().with_part_decimals(0).unwrap();
+        assert_eq!(
+            convert_units_with_options("5415.25 s", "h:m:s", &options).unwrap(),
+            "1:30:15.25"
+        );
+    }
+
+    #[test]
+    fn test_negative_values() {
+        assert_eq!(convert_units("-5 meters", "feet").unwrap(), "-16.4042 feet");
+        assert_eq!(
+            convert_units("-10 celsius", "fahrenheit").unwrap(),
+            "14 fahrenheit"
+        );
+        assert_eq!(
+            convert_units("-2.5 kilograms", "pounds").unwrap(),
+            "-5.51156 pounds"
+        );
+    }
+
+    #[test]
+    fn test_zero_values() {
+        assert_eq!(convert_units("0 meters", "feet").unwrap(), "0 feet");
+        assert_eq!(convert_units("0 kilograms", "pounds").unwrap(), "0 pounds");
+        assert_eq!(convert_units("0 liters", "gallons").unwrap(), "0 gallons");
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        assert_eq!(convert_units("1 METER", "FEET").unwrap(), "3.28084 feet");
+        assert_eq!(convert_units("1 Meter", "Feet").unwrap(), "3.28084 feet");
         assert_eq!(convert_units("1 mEtEr", "FeEt").unwrap(), "3.28084 feet");
     }
 
     #[test]
-    fn test_whitespace_handling() {
+    fn test_whitespace_handling() {
+        assert_eq!(
+            convert_units("  1 meter  ", "feet").unwrap(),
+            "3.28084 feet"
+        );
+        assert_eq!(convert_units("1  meter", "feet").unwrap(), "3.28084 feet");
+        assert_eq!(
+            convert_units("1 meter", "  feet  ").unwrap(),
+            "3.28084 feet"
+        );
+    }
+
+    #[test]
+    fn test_velocity_miles_per_hour() {
+        assert_eq!(
+            convert_units("60 miles/hour", "kilometers/hour").unwrap(),
+            "96.5606 kilometers/hour"
+        );
+        assert_eq!(
+            convert_units("100 kilometers/hour", "miles/hour").unwrap(),
+            "62.1371 miles/hour"
+        );
+        assert_eq!(
+            convert_units("30 meters/second", "miles/hour").unwrap(),
+            "67.1081 miles/hour"
+        );
+        assert_eq!(
+            convert_units("88 feet/second", "miles/hour").unwrap(),
+            "60 miles/hour"
+        );
+    }
+
+    #[test]
+    fn test_velocity_alternative_formats() {
+        assert_eq!(
+            convert_units("60 miles per hour", "km/h").unwrap(),
+            "96.5606 km/h"
+        );
+        assert_eq!(convert_units("60 mph", "kph").unwrap(), "96.5606 kph");
+        assert_eq!(convert_units("100 kmh", "mph").unwrap(), "62.1371 mph");
+        assert_eq!(convert_units("30 m/s", "ft/s").unwrap(), "98.4252 ft/s");
+    }
+
+    #[test]
+    fn test_unit_multiplication_area() {
+        assert_eq!(
+            convert_units("10 meters * 5 meters", "square feet").unwrap(),
+            "538.196 square feet"
+        );
+        assert_eq!(
+            convert_units("100 square meters", "square feet").unwrap(),
+            "1076.39 square feet"
+        );
+        assert_eq!(
+            convert_units("1 square kilometer", "square miles").unwrap(),
+            "0.386102 square miles"
+        );
+        assert_eq!(
+            convert_units("1 acre", "square meters").unwrap(),
+            "4046.87 square meters"
+        );
+    }
+
+    #[test]
+    fn test_unit_multiplication_volume() {
+        assert_eq!(
+            convert_units("2 meters * 3 meters * 4 meters", "cubic feet").unwrap(),
+            "847.552 cubic feet"
+        );
+        assert_eq!(
+            convert_units("1 cubic meter", "liters").unwrap(),
+            "1000 liters"
+        );
+        assert_eq!(
+            convert_units("1 cubic foot", "gallons").unwrap(),
+            "7.48052 gallons"
+        );
+        assert_eq!(
+            convert_units("100 cubic centimeters", "cubic inches").unwrap(),
+            "6.10237 cubic inches"
+        );
+    }
+
+    #[test]
+    fn test_unit_division_density() {
+        assert_eq!(
+            convert_units("1000 kilograms / cubic meter", "pounds / cubic foot").unwrap(),
+            "62.428 pounds / cubic foot"
+        );
+        assert_eq!(
+            convert_units("8.96 grams / cubic centimeter", "pounds / cubic inch").unwrap(),
+            "0.323701 pounds / cubic inch"
+        );
+        assert_eq!(
+            convert_units("1 gram / milliliter", "kilograms / liter").unwrap(),
+            "1 kilograms / liter"
+        );
+    }
+
+    #[test]
+    fn test_unit_division_fuel_economy() {
+        assert_eq!(
+            convert_units("30 miles / gallon", "kilometers / liter").unwrap(),
+            "12.7543 kilometers / liter"
+        );
+        assert_eq!(
+            convert_units("8 liters / 100 kilometers", "miles / gallon").unwrap(),
+            "29.4018 miles / gallon"
+        );
+        assert_eq!(
+            convert_units("25 miles per gallon", "liters per 100 kilometers").unwrap(),
+            "9.40858 liters per 100 kilometers"
+        );
+    }
+
+    #[test]
+    fn test_complex_unit_expressions() {
+        assert_eq!(
+            convert_units("9.8 meters / second^2", "feet / second^2").unwrap(),
+            "32.1522 feet / second^2"
+        );
+        assert_eq!(
+            convert_units("1 newton", "pounds force").unwrap(),
+            "0.224809 pounds force"
+        );
+        assert_eq!(
+            convert_units("1 joule", "foot pounds").unwrap(),
+            "0.737562 foot pounds"
+        );
+        assert_eq!(
+            convert_units("100 watts", "horsepower").unwrap(),
+            "0.134102 horsepower"
+        );
+    }
+
+    #[test]
+    fn test_invalid_compound_units() {
+        assert_eq!(
+            convert_units("5 kilograms blorps", "pounds inches")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown unit 'blorps'"
+        );
+        assert_eq!(
+            convert_units("1 meter / meter", "feet")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unit cancellation not supported"
+        );
+    }
+
+    #[test]
+    fn test_dimensional_analysis() {
+        assert_eq!(
+            convert_units("10 kilograms * meters / second^2", "newtons").unwrap(),
+            "10 newtons"
+        );
+        assert_eq!(
+            convert_units("1 pound force / inch^2", "newtons / square meter").unwrap(),
+            "6894.76 newtons / square meter"
+        );
+        assert_eq!(
+            convert_units(
+                "5 watts / (square meters * kelvin)",
+                "watts / (square feet * kelvin)"
+            )
+            .unwrap(),
+            "0.464515 watts / (square feet * kelvin)"
+        );
+        assert_eq!(
+            convert_units("1 horsepower * hour", "joules").unwrap(),
+            "2684519.54 joules"
+        );
+        assert_eq!(convert_units("36 km/h", "m/s").unwrap(), "10 m/s");
+    }
+
+    #[test]
+    fn test_dimension_mismatch_errors() {
+        assert_eq!(
+            convert_units("1 kilogram * meter", "joules").unwrap_err(),
+            ConversionError::UnknownCompoundUnit
+        );
+        assert_eq!(
+            convert_units("1 newton", "watts").unwrap_err(),
+            ConversionError::IncompatibleUnits {
+                from: "force".to_string(),
+                to: "power".to_string()
+            }
+        );
+        assert_eq!(
+            convert_units("1 m^127 * m", "m").unwrap_err(),
+            ConversionError::ExponentOutOfRange
+        );
+        assert_eq!(
+            convert_units("1 (m^100)^2", "m").unwrap_err().to_string(),
+            "Error: Unit exponent out of range"
+        );
+    }
+
+    #[test]
+    fn test_parentheses_in_expressions() {
+        assert_eq!(
+            convert_units("60 miles / (1 hour)", "meters / second").unwrap(),
+            "26.8224 meters / second"
+        );
+        assert_eq!(
+            convert_units("(10 kilograms) / (2 meters)^3", "pounds / cubic foot").unwrap(),
+            "0.078035 pounds / cubic foot"
+        );
+        assert_eq!(
+            convert_units("5 * (meters / second)", "feet / second").unwrap(),
+            "16.4042 feet / second"
+        );
+    }
+
+    #[test]
+    fn test_expression_grouping_and_precedence() {
+        assert_eq!(
+            convert_units("(10 m)*(5 m)", "square feet").unwrap(),
+            "538.196 square feet"
+        );
+        assert_eq!(convert_units("10m", "feet").unwrap(), "32.8084 feet");
+        assert_eq!(
+            convert_units("10 m*5 m", "square meters").unwrap(),
+            "50 square meters"
+        );
+        assert_eq!(convert_units("2 (ft/s)", "m/s").unwrap(), "0.6096 m/s");
+        // Side-by-side multiplication binds tighter than an explicit divide
+        assert_eq!(convert_units("100 m / 10 s", "m/s").unwrap(), "10 m/s");
+        assert_eq!(
+            convert_units("2 meters^2 * 3 meters", "liters").unwrap(),
+            "6000 liters"
+        );
+        assert_eq!(
+            convert_units("5 kilograms meters", "pounds inches").unwrap(),
+            "433.981 pounds inches"
+        );
+        assert_eq!(
+            convert_units("1e3 meters", "kilometers").unwrap(),
+            "1 kilometer"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_positions() {
+        assert_eq!(
+            convert_units("(10 m", "feet").unwrap_err(),
+            ConversionError::ParseError {
+                position: 5,
+                message: "expected ')'".to_string()
+            }
+        );
+        assert_eq!(
+            convert_units("10 m)", "feet").unwrap_err(),
+            ConversionError::ParseError {
+                position: 4,
+                message: "unmatched ')'".to_string()
+            }
+        );
+        assert_eq!(
+            convert_units("10 m ^ x", "feet").unwrap_err(),
+            ConversionError::ParseError {
+                position: 7,
+                message: "expected a whole-number exponent".to_string()
+            }
+        );
+        assert_eq!(
+            convert_units("10 m #", "feet").unwrap_err(),
+            ConversionError::ParseError {
+                position: 5,
+                message: "unexpected character '#'".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_exponent_limits() {
+        assert_eq!(convert_units("1 m^127 / m^126", "m").unwrap(), "1 m");
+        assert_eq!(
+            convert_units("1 m^128", "m").unwrap_err(),
+            ConversionError::ParseError {
+                position: 4,
+                message: "expected a whole-number exponent".to_string()
+            }
+        );
+        assert_eq!(
+            convert_units("1 m^-127 / m^2", "m").unwrap_err(),
+            ConversionError::ExponentOutOfRange
+        );
+        assert_eq!(
+            convert_units("1 (m^64)^2", "m").unwrap_err(),
+            ConversionError::ExponentOutOfRange
+        );
+    }
+
+    #[test]
+    fn test_si_prefixes() {
+        assert_eq!(
+            convert_units("25.4 millimeters", "inches").unwrap(),
+            "1 inch"
+        );
+        assert_eq!(
+            convert_units("30.48 centimeters", "feet").unwrap(),
+            "1 foot"
+        );
+        assert_eq!(
+            convert_units("1500 micrometers", "millimeters").unwrap(),
+            "1.5 millimeters"
+        );
+        assert_eq!(convert_units("2 MW", "kW").unwrap(), "2000 kW");
+        assert_eq!(convert_units("2 mW", "W").unwrap(), "0.002 W");
+        assert_eq!(
+            convert_units("4.184 kilojoules", "joules").unwrap(),
+            "4184 joules"
+        );
+        assert_eq!(
+            convert_units("500 milligrams", "grams").unwrap(),
+            "0.5 grams"
+        );
+        assert_eq!(convert_units("1 kg", "pounds").unwrap(), "2.20462 pounds");
+        assert_eq!(convert_units("250 µm", "mm").unwrap(), "0.25 mm");
+        assert_eq!(convert_units("250 um", "mm").unwrap(), "0.25 mm");
+        assert_eq!(convert_units("1 Mm", "km").unwrap(), "1000 km");
+        assert_eq!(
+            convert_units("1 gigawatt", "megawatts").unwrap(),
+            "1000 megawatts"
+        );
+        assert_eq!(
+            convert_units("1 quettameter", "ronnameters").unwrap(),
+            "1000 ronnameters"
+        );
+    }
+
+    #[test]
+    fn test_prefixes_need_prefixable_units() {
+        assert_eq!(
+            convert_units("1 kilofoot", "meters")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown unit 'kilofoot'"
+        );
+        assert_eq!(
+            convert_units("1 millicelsius", "kelvin")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown unit 'millicelsius'"
+        );
+    }
+
+    #[test]
+    fn test_unit_symbols() {
+        assert_eq!(convert_units("10 m", "ft").unwrap(), "32.8084 ft");
+        assert_eq!(convert_units("5 ft", "m").unwrap(), "1.524 m");
+        assert_eq!(convert_units("3 kg", "lb").unwrap(), "6.61387 lb");
+        assert_eq!(convert_units("2 lb", "kg").unwrap(), "0.907185 kg");
+        assert_eq!(convert_units("20 °C", "°F").unwrap(), "68 °F");
+        assert_eq!(
+            convert_units("20°C", "fahrenheit").unwrap(),
+            "68 fahrenheit"
+        );
+        assert_eq!(convert_units("1 L", "gal").unwrap(), "0.264172 gal");
+        assert_eq!(convert_units("4 in", "cm").unwrap(), "10.16 cm");
+        assert_eq!(convert_units("12 oz", "g").unwrap(), "340.194 g");
+        assert_eq!(convert_units("1 hp", "W").unwrap(), "745.7 W");
+        assert_eq!(convert_units("3 lbf * ft", "J").unwrap(), "4.06745 J");
+    }
+
+    #[test]
+    fn test_symbol_case_precedence() {
+        // Symbols are case-sensitive: milli and mega are different prefixes
+        assert_eq!(convert_units("1 mm", "m").unwrap(), "0.001 m");
+        assert_eq!(convert_units("1 Mm", "m").unwrap(), "1000000 m");
+        // Names ignore case, and a symbol in the wrong case is accepted when it can
+        // only mean one unit
+        assert_eq!(convert_units("1 KG", "g").unwrap(), "1000 g");
+        assert_eq!(convert_units("1 Kilogram", "g").unwrap(), "1000 g");
+        assert_eq!(
+            convert_units("1 MM", "m").unwrap_err().to_string(),
+            "Error: Ambiguous unit 'MM', symbols are case-sensitive (did you mean Mm or mm?)"
+        );
+        // An unprefixed symbol beats prefixed ones, so "pa" isn't a picoampere
+        assert_eq!(convert_units("1 pa", "kPa").unwrap(), "0.001 kPa");
+        // Then a prefix in the wrong case beats a unit in the wrong case
+        assert_eq!(convert_units("1 KB", "B").unwrap(), "1000 B");
+        assert_eq!(convert_units("1 Kb", "b").unwrap(), "1000 b");
+        assert_eq!(convert_units("1 cc", "mL").unwrap(), "1 mL");
+        assert_eq!(convert_units("10 Nm", "J").unwrap(), "10 J");
+    }
+
+    #[test]
+    fn test_absolute_temperature_scales() {
+        assert_eq!(
+            convert_units("0 celsius", "kelvin").unwrap(),
+            "273.15 kelvin"
+        );
+        assert_eq!(convert_units("300 K", "°C").unwrap(), "26.85 °C");
+        assert_eq!(
+            convert_units("0 fahrenheit", "rankine").unwrap(),
+            "459.67 rankine"
+        );
+        assert_eq!(convert_units("100 kelvin", "°R").unwrap(), "180 °R");
+        assert_eq!(
+            convert_units("100 celsius", "réaumur").unwrap(),
+            "80 réaumur"
+        );
+        assert_eq!(convert_units("20 °Re", "°C").unwrap(), "25 °C");
+        assert_eq!(
+            convert_units("100 celsius", "delisle").unwrap(),
+            "0 delisle"
+        );
+        assert_eq!(convert_units("0 celsius", "°De").unwrap(), "150 °De");
+        assert_eq!(convert_units("-40 °C", "°F").unwrap(), "-40 °F");
+    }
+
+    #[test]
+    fn test_temperature_differences() {
+        assert_eq!(
+            convert_units("10 delta celsius", "delta fahrenheit").unwrap(),
+            "18 delta fahrenheit"
+        );
+        assert_eq!(convert_units("18 Δ°F", "Δ°C").unwrap(), "10 Δ°C");
+        assert_eq!(
+            convert_units("5 kelvin difference", "delta fahrenheit").unwrap(),
+            "9 delta fahrenheit"
+        );
+        assert_eq!(convert_units("9 ∆°F", "K").unwrap(), "5 K");
+        assert_eq!(convert_units("10 delta °C", "°R").unwrap(), "18 °R");
+        assert_eq!(
+            convert_units("10 delta celsius", "fahrenheit")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from temperature difference to temperature"
+        );
+        assert_eq!(
+            convert_units("10 celsius", "delta fahrenheit")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from temperature to temperature difference"
+        );
+        assert_eq!(
+            convert_units("10 psig * 2", "psig")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from pressure difference to gauge pressure"
+        );
+        assert_eq!(
+            convert_units("1 delta meter", "feet")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown unit 'delta'"
+        );
+    }
+
+    #[test]
+    fn test_temperature_in_compounds_is_an_interval() {
         assert_eq!(
-            convert_units("  1 meter  ", "feet").unwrap(),
-            "3.28084 feet"
+            convert_units("4184 J/(kg*K)", "J/(kg*celsius)").unwrap(),
+            "4184 J/(kg*celsius)"
         );
-        assert_eq!(convert_units("1  meter", "feet").unwrap(), "3.28084 feet");
         assert_eq!(
-            convert_units("1 meter", "  feet  ").unwrap(),
-            "3.28084 feet"
+            convert_units("1 J/(g*°C)", "joules / (pound * fahrenheit)").unwrap(),
+            "251.996 joules / (pound * fahrenheit)"
+        );
+        assert_eq!(
+            convert_units("10 meters / celsius", "feet / fahrenheit").unwrap(),
+            "18.2269 feet / fahrenheit"
+        );
+        assert_eq!(
+            convert_units("1 celsius * meter", "kelvin * meters").unwrap(),
+            "1 kelvin * meters"
         );
     }
 
     #[test]
-    fn test_velocity_miles_per_hour() {
+    fn test_time_units() {
+        assert_eq!(convert_units("90 minutes", "hours").unwrap(), "1.5 hours");
+        assert_eq!(convert_units("1 day", "hours").unwrap(), "24 hours");
+        assert_eq!(convert_units("2 weeks", "days").unwrap(), "14 days");
+        assert_eq!(convert_units("1 year", "days").unwrap(), "365.25 days");
+        assert_eq!(convert_units("1 century", "years").unwrap(), "100 years");
+        assert_eq!(convert_units("250 ms", "s").unwrap(), "0.25 s");
+        assert_eq!(convert_units("1 µs", "ns").unwrap(), "1000 ns");
         assert_eq!(
-            convert_units("60 miles/hour", "kilometers/hour").unwrap(),
-            "96.5606 kilometers/hour"
+            convert_units("3 milliseconds", "microseconds").unwrap(),
+            "3000 microseconds"
         );
+        assert_eq!(convert_units("60 km/h", "km/min").unwrap(), "1 km/min");
         assert_eq!(
-            convert_units("100 kilometers/hour", "miles/hour").unwrap(),
-            "62.1371 miles/hour"
+            convert_units("9.80665 m/s^2", "km/min^2").unwrap(),
+            "35.3039 km/min^2"
         );
         assert_eq!(
-            convert_units("30 meters/second", "miles/hour").unwrap(),
-            "67.1081 miles/hour"
+            convert_units("1 hour", "meters").unwrap_err().to_string(),
+            "Error: Cannot convert from time to length"
         );
+    }
+
+    #[test]
+    fn test_clock_durations() {
+        assert_eq!(convert_units("1:30:00", "minutes").unwrap(), "90 minutes");
+        assert_eq!(convert_units("0:45", "hours").unwrap(), "0.75 hours");
         assert_eq!(
-            convert_units("88 feet/second", "miles/hour").unwrap(),
-            "60 miles/hour"
+            convert_units("1h 30m 15s", "seconds").unwrap(),
+            "5415 seconds"
+        );
+        assert_eq!(convert_units("2d 12h", "days").unwrap(), "2.5 days");
+        assert_eq!(convert_units("5415 s", "h:m:s").unwrap(), "1:30:15");
+        assert_eq!(
+            convert_units("5415 seconds", "h m s").unwrap(),
+            "1h 30m 15s"
+        );
+        assert_eq!(convert_units("1.5 hours", "hms").unwrap(), "1h 30m");
+        assert_eq!(convert_units("59.9999 s", "h:mm:ss").unwrap(), "0:01:00");
+        assert_eq!(convert_units("-90 min", "h:m:s").unwrap(), "-1:30:00");
+        assert_eq!(convert_units("1:30:00", "h m s").unwrap(), "1h 30m");
+        // A single compact component is still a unit: 30m is thirty meters
+        assert_eq!(convert_units("30m", "ft").unwrap(), "98.4252 ft");
+        // And spaced out, m is a meter whichever order the terms come in
+        assert_eq!(
+            convert_units("2 m 30 s", "s").unwrap_err().to_string(),
+            "Error: Cannot add time to length"
+        );
+        assert_eq!(
+            convert_units("30 s 2 m", "s").unwrap_err().to_string(),
+            "Error: Cannot add length to time"
+        );
+        assert_eq!(
+            convert_units("1 meter", "h:m:s").unwrap_err().to_string(),
+            "Error: Cannot convert from length to time"
+        );
+        assert_eq!(
+            convert_units("1e20 s", "h:m:s").unwrap_err(),
+            ConversionError::ValueOutOfRange
+        );
+        assert_eq!(
+            convert_units("1:75", "minutes").unwrap_err().to_string(),
+            "Error: Invalid input format at byte 1: unexpected character ':'"
         );
     }
 
     #[test]
-    fn test_velocity_alternative_formats() {
+    fn test_information_units() {
+        assert_eq!(convert_units("1 byte", "bits").unwrap(), "8 bits");
+        assert_eq!(convert_units("2 nibbles", "bytes").unwrap(), "1 byte");
+        assert_eq!(convert_units("1 kB", "B").unwrap(), "1000 B");
+        assert_eq!(convert_units("1 KiB", "B").unwrap(), "1024 B");
+        assert_eq!(convert_units("1 GiB", "MB").unwrap(), "1073.74 MB");
+        assert_eq!(convert_units("1 TB", "GiB").unwrap(), "931.323 GiB");
+        assert_eq!(convert_units("1 MB", "Mb").unwrap(), "8 Mb");
+        assert_eq!(
+            convert_units("3 mebibytes", "kibibytes").unwrap(),
+            "3072 kibibytes"
+        );
+        assert_eq!(convert_units("1 Gbit", "MB").unwrap(), "125 MB");
+        // Sub-unit prefixes make no sense for data, so "mb" is a megabit with the prefix
+        // in the wrong case
+        assert_eq!(convert_units("5 mb", "kB").unwrap(), "625 kB");
+    }
+
+    #[test]
+    fn test_data_rates() {
+        assert_eq!(convert_units("100 Mbps", "MB/s").unwrap(), "12.5 MB/s");
+        assert_eq!(convert_units("1 Gbps", "Mbps").unwrap(), "1000 Mbps");
         assert_eq!(
-            convert_units("60 miles per hour", "km/h").unwrap(),
-            "96.5606 km/h"
+            convert_units("10 MB/s", "GiB/hour").unwrap(),
+            "33.5276 GiB/hour"
+        );
+        assert_eq!(
+            convert_units("1 gigabit / second", "megabytes / second").unwrap(),
+            "125 megabytes / second"
+        );
+        assert_eq!(
+            convert_units("1 GB", "Mbps").unwrap_err().to_string(),
+            "Error: Cannot convert from information to data rate"
         );
-        assert_eq!(convert_units("60 mph", "kph").unwrap(), "96.5606 kph");
-        assert_eq!(convert_units("100 kmh", "mph").unwrap(), "62.1371 mph");
-        assert_eq!(convert_units("30 m/s", "ft/s").unwrap(), "98.4252 ft/s");
     }
 
     #[test]
-    fn test_unit_multiplication_area() {
+    fn test_prefix_family_output() {
         assert_eq!(
-            convert_units("10 meters * 5 meters", "square feet").unwrap(),
-            "538.196 square feet"
+            convert_units("1500000000 B", "decimal bytes").unwrap(),
+            "1.5 GB"
         );
         assert_eq!(
-            convert_units("100 square meters", "square feet").unwrap(),
-            "1076.39 square feet"
+            convert_units("1500000000 B", "binary bytes").unwrap(),
+            "1.39698 GiB"
         );
         assert_eq!(
-            convert_units("1 square kilometer", "square miles").unwrap(),
-            "0.386102 square miles"
+            convert_units("1 GiB", "decimal bytes").unwrap(),
+            "1.07374 GB"
         );
+        assert_eq!(convert_units("1 GB", "binary bits").unwrap(), "7.45058 Gib");
+        assert_eq!(convert_units("100 B", "binary bytes").unwrap(), "100 B");
         assert_eq!(
-            convert_units("1 acre", "square meters").unwrap(),
-            "4046.87 square meters"
+            convert_units("2500000 bps", "decimal bps").unwrap(),
+            "2.5 Mbps"
         );
     }
 
     #[test]
-    fn test_unit_multiplication_volume() {
+    fn test_pressure_units() {
+        assert_eq!(convert_units("1 atm", "kPa").unwrap(), "101.325 kPa");
+        assert_eq!(convert_units("1 bar", "psi").unwrap(), "14.5038 psi");
+        assert_eq!(convert_units("1013.25 mbar", "atm").unwrap(), "1 atm");
+        assert_eq!(convert_units("1 millibar", "hPa").unwrap(), "1 hPa");
+        assert_eq!(convert_units("760 torr", "atm").unwrap(), "1 atm");
+        assert_eq!(convert_units("120 mmHg", "kPa").unwrap(), "15.9987 kPa");
+        assert_eq!(convert_units("29.92 inHg", "hPa").unwrap(), "1013.21 hPa");
+        assert_eq!(convert_units("1 ksi", "MPa").unwrap(), "6.89476 MPa");
+        assert_eq!(convert_units("1 kgf/cm²", "bar").unwrap(), "0.980665 bars");
+        assert_eq!(convert_units("10 inH2O", "Pa").unwrap(), "2490.89 Pa");
         assert_eq!(
-            convert_units("2 meters * 3 meters * 4 meters", "cubic feet").unwrap(),
-            "847.552 cubic feet"
+            convert_units("1 inch of water", "pascals").unwrap(),
+            "249.089 pascals"
         );
         assert_eq!(
-            convert_units("1 cubic meter", "liters").unwrap(),
-            "1000 liters"
+            convert_units("1 pound force / square inch", "psi").unwrap(),
+            "1 psi"
         );
         assert_eq!(
-            convert_units("1 cubic foot", "gallons").unwrap(),
-            "7.48052 gallons"
+            convert_units("1 psi", "newtons").unwrap_err().to_string(),
+            "Error: Cannot convert from pressure to force"
+        );
+    }
+
+    #[test]
+    fn test_gauge_pressure() {
+        assert_eq!(convert_units("0 psig", "psia").unwrap(), "14.6959 psia");
+        assert_eq!(convert_units("30 psig", "psi").unwrap(), "44.6959 psi");
+        assert_eq!(convert_units("101.325 kPa", "psig").unwrap(), "0 psig");
+        assert_eq!(convert_units("2 barg", "kPa").unwrap(), "301.325 kPa");
+        assert_eq!(convert_units("2 barg", "psig").unwrap(), "29.0075 psig");
+
+        let thin_air = ConversionOptions::default()
+            .with_ambient_pressure("12 psi")
+            .unwrap();
+        assert_eq!(
+            convert_units_with_options("0 psig", "psia", &thin_air).unwrap(),
+            "12 psia"
         );
         assert_eq!(
-            convert_units("100 cubic centimeters", "cubic inches").unwrap(),
-            "6.10238 cubic inches"
+            convert_units_with_options("20 psia", "psig", &thin_air).unwrap(),
+            "8 psig"
+        );
+        assert_eq!(
+            ConversionOptions::default()
+                .with_ambient_pressure("12 meters")
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from length to pressure"
         );
     }
 
     #[test]
-    fn test_unit_division_density() {
+    fn test_electrical_units() {
+        assert_eq!(convert_units("1500 mV", "V").unwrap(), "1.5 V");
+        assert_eq!(convert_units("250 mA", "amperes").unwrap(), "0.25 amperes");
+        assert_eq!(convert_units("4.7 kΩ", "ohms").unwrap(), "4700 ohms");
+        assert_eq!(convert_units("1 MΩ", "kΩ").unwrap(), "1000 kΩ");
+        assert_eq!(convert_units("0.01 S", "mS").unwrap(), "10 mS");
+        assert_eq!(convert_units("100 nF", "µF").unwrap(), "0.1 µF");
+        assert_eq!(convert_units("22 pF", "nF").unwrap(), "0.022 nF");
+        assert_eq!(convert_units("2 henries", "mH").unwrap(), "2000 mH");
+        assert_eq!(convert_units("3 A * 4 Ω", "V").unwrap(), "12 V");
+        assert_eq!(convert_units("12 V / 4 A", "ohms").unwrap(), "3 ohms");
+        assert_eq!(convert_units("1 / (2 ohms)", "S").unwrap(), "0.5 S");
+        assert_eq!(convert_units("10 µF * 5 V", "µC").unwrap(), "50 µC");
+        assert_eq!(
+            convert_units("1 volt", "amperes").unwrap_err().to_string(),
+            "Error: Cannot convert from voltage to current"
+        );
+    }
+
+    #[test]
+    fn test_magnetic_units() {
+        assert_eq!(convert_units("1 T", "gauss").unwrap(), "10000 gauss");
+        assert_eq!(convert_units("0.5 G", "µT").unwrap(), "50 µT");
+        assert_eq!(convert_units("1 Wb", "Mx").unwrap(), "100000000 Mx");
+        assert_eq!(convert_units("2 Wb / 4 m^2", "T").unwrap(), "0.5 T");
+        assert_eq!(convert_units("3 Wb / 1.5 A", "H").unwrap(), "2 H");
+    }
+
+    #[test]
+    fn test_electrical_combinations() {
+        assert_eq!(
+            convert_units("3000 mA*h", "coulombs").unwrap(),
+            "10800 coulombs"
+        );
+        assert_eq!(convert_units("3000 mAh", "Ah").unwrap(), "3 Ah");
+        assert_eq!(convert_units("1 kW*h", "MJ").unwrap(), "3.6 MJ");
+        assert_eq!(convert_units("230 V * 10 A", "kW").unwrap(), "2.3 kW");
+        assert_eq!(convert_units("1500 W", "V*A").unwrap(), "1500 V*A");
+        // Symbols that differ only in case are different units
+        assert_eq!(convert_units("1 pA", "fA").unwrap(), "1000 fA");
+        assert_eq!(convert_units("1 PA", "TA").unwrap(), "1000 TA");
+    }
+
+    #[test]
+    fn test_angle_units() {
         assert_eq!(
-            convert_units("1000 kilograms / cubic meter", "pounds / cubic foot").unwrap(),
-            "62.428 pounds / cubic foot"
+            convert_units("180 degrees", "radians").unwrap(),
+            "3.14159 radians"
         );
+        assert_eq!(convert_units("1 rad", "°").unwrap(), "57.2958 °");
+        assert_eq!(convert_units("90°", "grad").unwrap(), "100 grad");
         assert_eq!(
-            convert_units("8.96 grams / cubic centimeter", "pounds / cubic inch").unwrap(),
-            "0.3237 pounds / cubic inch"
+            convert_units("0.25 turns", "degrees").unwrap(),
+            "90 degrees"
         );
         assert_eq!(
-            convert_units("1 gram / milliliter", "kilograms / liter").unwrap(),
-            "1 kilograms / liter"
+            convert_units("1 degree", "arcminutes").unwrap(),
+            "60 arcminutes"
+        );
+        assert_eq!(convert_units("1′", "″").unwrap(), "60 ″");
+        assert_eq!(convert_units("1 arcsec", "mrad").unwrap(), "0.004848 mrad");
+        assert_eq!(
+            convert_units("1600 angular mils", "degrees").unwrap(),
+            "90 degrees"
+        );
+        assert_eq!(
+            convert_units("1 mil", "degrees").unwrap_err().to_string(),
+            "Error: Cannot convert from length to angle"
         );
     }
 
     #[test]
-    fn test_unit_division_fuel_economy() {
+    fn test_frequency_and_angular_velocity() {
+        assert_eq!(convert_units("2.4 GHz", "MHz").unwrap(), "2400 MHz");
+        assert_eq!(convert_units("50 Hz", "rpm").unwrap(), "3000 rpm");
+        assert_eq!(convert_units("3000 rpm", "Hz").unwrap(), "50 Hz");
+        assert_eq!(convert_units("60 rpm", "rad/s").unwrap(), "6.28319 rad/s");
+        assert_eq!(convert_units("1 Hz", "rad/s").unwrap(), "6.28319 rad/s");
+        assert_eq!(convert_units("90 deg/s", "rpm").unwrap(), "15 rpm");
+        assert_eq!(
+            convert_units("120 cycles per minute", "Hz").unwrap(),
+            "2 Hz"
+        );
+        assert_eq!(convert_units("1 / (20 ms)", "Hz").unwrap(), "50 Hz");
+    }
+
+    #[test]
+    fn test_radians_are_not_dimensionless() {
         assert_eq!(
-            convert_units("30 miles / gallon", "kilometers / liter").unwrap(),
-            "12.7543 kilometers / liter"
+            convert_units("1 rad", "meters").unwrap_err().to_string(),
+            "Error: Cannot convert from angle to length"
         );
         assert_eq!(
-            convert_units("8 liters / 100 kilometers", "miles / gallon").unwrap(),
-            "29.4018 miles / gallon"
+            convert_units("1 rad", "Hz").unwrap_err().to_string(),
+            "Error: Cannot convert from angle to frequency"
         );
         assert_eq!(
-            convert_units("25 miles per gallon", "liters per 100 kilometers").unwrap(),
-            "9.40858 liters per 100 kilometers"
+            convert_units("2 m * 1 rad", "m").unwrap_err(),
+            ConversionError::UnknownCompoundUnit
         );
     }
 
     #[test]
-    fn test_complex_unit_expressions() {
+    fn test_energy_units() {
+        assert_eq!(convert_units("1 kWh", "MJ").unwrap(), "3.6 MJ");
         assert_eq!(
-            convert_units("9.8 meters / second^2", "feet / second^2").unwrap(),
-            "32.1522 feet / second^2"
+            convert_units("1 kilowatt hour", "joules").unwrap(),
+            "3600000 joules"
         );
+        assert_eq!(convert_units("1 BTU", "J").unwrap(), "1055.06 J");
         assert_eq!(
-            convert_units("1 newton", "pounds force").unwrap(),
-            "0.224809 pounds force"
+            convert_units("1 thermochemical BTU", "J").unwrap(),
+            "1054.35 J"
         );
+        assert_eq!(convert_units("1000 BTU", "kWh").unwrap(), "0.293071 kWh");
+        assert_eq!(convert_units("1 therm", "kWh").unwrap(), "29.3071 kWh");
+        assert_eq!(convert_units("1 US therm", "MJ").unwrap(), "105.48 MJ");
+        assert_eq!(convert_units("1 MeV", "keV").unwrap(), "1000 keV");
+        assert_eq!(convert_units("1 GeV", "eV").unwrap(), "1000000000 eV");
         assert_eq!(
-            convert_units("1 joule", "foot pounds").unwrap(),
-            "0.737562 foot pounds"
+            convert_units("15 kilotons of TNT", "TJ").unwrap(),
+            "62.76 TJ"
+        );
+        assert_eq!(convert_units("1 erg", "µJ").unwrap(), "0.1 µJ");
+    }
+
+    #[test]
+    fn test_calorie_variants() {
+        assert_eq!(convert_units("1 cal", "J").unwrap(), "4.184 J");
+        assert_eq!(convert_units("1 IT calorie", "J").unwrap(), "4.1868 J");
+        assert_eq!(convert_units("1 cal_IT", "cal").unwrap(), "1.00067 cal");
+        assert_eq!(convert_units("1 kcal", "kJ").unwrap(), "4.184 kJ");
+        assert_eq!(convert_units("2000 Cal", "kcal").unwrap(), "2000 kcal");
+        assert_eq!(
+            convert_units("1 food calorie", "calories").unwrap(),
+            "1000 calories"
         );
         assert_eq!(
-            convert_units("100 watts", "horsepower").unwrap(),
-            "0.134102 horsepower"
+            convert_units("1 CAL", "J").unwrap_err().to_string(),
+            "Error: Ambiguous unit 'CAL', symbols are case-sensitive (did you mean cal or Cal?)"
         );
     }
 
     #[test]
-    fn test_invalid_compound_units() {
+    fn test_power_units() {
+        assert_eq!(convert_units("1 hp", "W").unwrap(), "745.7 W");
+        assert_eq!(
+            convert_units("1 metric horsepower", "W").unwrap(),
+            "735.499 W"
+        );
+        assert_eq!(convert_units("100 PS", "hp").unwrap(), "98.632 hp");
         assert_eq!(
-            convert_units("10 meters / celsius", "feet / fahrenheit")
+            convert_units("1 electrical horsepower", "W").unwrap(),
+            "746 W"
+        );
+        assert_eq!(convert_units("1 hp_E", "hp").unwrap(), "1.0004 hp");
+        assert_eq!(convert_units("12000 BTU/h", "W").unwrap(), "3516.85 W");
+        assert_eq!(
+            convert_units("1 ton of refrigeration", "BTU/h").unwrap(),
+            "12000 BTU/h"
+        );
+        assert_eq!(convert_units("3 TR", "kW").unwrap(), "10.5506 kW");
+    }
+
+    #[test]
+    fn test_precision() {
+        // The default is unchanged
+        assert_eq!(convert_units("123456.789 m", "m").unwrap(), "123456.79 m");
+        assert_eq!(convert_units("0.0000001 m", "m").unwrap(), "0 m");
+
+        let options = ConversionOptions::default()
+            .with_significant_figures(8)
+            .unwrap();
+        assert_eq!(
+            convert_units_with_options("123456.789 m", "m", &options).unwrap(),
+            "123456.79 m"
+        );
+        let options = ConversionOptions::default()
+            .with_significant_figures(3)
+            .unwrap();
+        assert_eq!(
+            convert_units_with_options("123456.789 m", "m", &options).unwrap(),
+            "123000 m"
+        );
+        assert_eq!(
+            convert_units_with_options("1.5 m", "m", &options).unwrap(),
+            "1.50 m"
+        );
+        // Rounding that carries into a new digit keeps the figures asked for
+        let options = ConversionOptions::default()
+            .with_significant_figures(4)
+            .unwrap();
+        assert_eq!(
+            convert_units_with_options("999.96 m", "m", &options).unwrap(),
+            "1000 m"
+        );
+        // ...and switches to E notation once it reaches 10^15
+        let options = ConversionOptions::default()
+            .with_significant_figures(2)
+            .unwrap();
+        assert_eq!(
+            convert_units_with_options("9.9999e14 m", "m", &options).unwrap(),
+            "1.0e15 m"
+        );
+        let options = ConversionOptions::default().with_decimals(3).unwrap();
+        assert_eq!(
+            convert_units_with_options("123456.789 m", "m", &options).unwrap(),
+            "123456.789 m"
+        );
+        assert_eq!(
+            convert_units_with_options("1 m", "m", &options).unwrap(),
+            "1.000 m"
+        );
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let options = ConversionOptions::default().with_decimals(2).unwrap();
+        assert_eq!(
+            convert_units_with_options("2.675 m", "m", &options).unwrap(),
+            "2.68 m"
+        );
+        assert_eq!(
+            convert_units_with_options("0.125 m", "m", &options).unwrap(),
+            "0.12 m"
+        );
+        assert_eq!(
+            convert_units_with_options("-0.375 m", "m", &options).unwrap(),
+            "-0.38 m"
+        );
+
+        let options = options.with_rounding("half-up").unwrap();
+        assert_eq!(
+            convert_units_with_options("2.675 m", "m", &options).unwrap(),
+            "2.68 m"
+        );
+        assert_eq!(
+            convert_units_with_options("0.125 m", "m", &options).unwrap(),
+            "0.13 m"
+        );
+        assert_eq!(
+            convert_units_with_options("9.995 m", "m", &options).unwrap(),
+            "10.00 m"
+        );
+
+        let options = options.with_rounding("truncate").unwrap();
+        assert_eq!(
+            convert_units_with_options("2.679 m", "m", &options).unwrap(),
+            "2.67 m"
+        );
+        assert_eq!(
+            convert_units_with_options("-0.129 m", "m", &options).unwrap(),
+            "-0.12 m"
+        );
+
+        assert_eq!(
+            ConversionOptions::default()
+                .with_rounding("up")
                 .unwrap_err()
                 .to_string(),
-            "Error: Invalid unit combination"
+            "Error: Unknown rounding mode 'up' (expected half-even, half-up or truncate)"
+        );
+    }
+
+    #[test]
+    fn test_notation() {
+        assert_eq!(convert_units("1 light year", "m").unwrap(), "9.46073e15 m");
+
+        let options = ConversionOptions::default().with_notation("auto").unwrap();
+        assert_eq!(
+            convert_units_with_options("0.0000001 m", "m", &options).unwrap(),
+            "1e-7 m"
+        );
+        assert_eq!(
+            convert_units_with_options("299792458 m/s", "m/s", &options).unwrap(),
+            "2.99792e8 m/s"
+        );
+        assert_eq!(
+            convert_units_with_options("123.456 m", "m", &options).unwrap(),
+            "123.456 m"
+        );
+
+        let options = ConversionOptions::default()
+            .with_notation("scientific")
+            .unwrap()
+            .with_significant_figures(3)
+            .unwrap();
+        assert_eq!(
+            convert_units_with_options("123456.789 m", "m", &options).unwrap(),
+            "1.23e5 m"
+        );
+        assert_eq!(
+            convert_units_with_options("0 m", "m", &options).unwrap(),
+            "0.00 m"
+        );
+
+        assert_eq!(
+            ConversionOptions::default()
+                .with_notation("engineering")
+                .unwrap_err()
+                .to_string(),
+            "Error: Unknown notation 'engineering' (expected standard, auto or scientific)"
+        );
+    }
+
+    #[test]
+    fn test_precision_limits() {
+        let options = ConversionOptions::default()
+            .with_significant_figures(17)
+            .unwrap();
+        assert_eq!(
+            convert_units_with_options("0.1 m", "m", &options).unwrap(),
+            "0.10000000000000000 m"
         );
         assert_eq!(
-            convert_units("5 kilograms meters", "pounds inches")
+            ConversionOptions::default()
+                .with_significant_figures(0)
                 .unwrap_err()
                 .to_string(),
-            "Error: Unknown unit 'kilograms meters'"
+            "Error: significant figures must be from 1 to 17"
         );
         assert_eq!(
-            convert_units("1 meter / meter", "feet")
+            ConversionOptions::default()
+                .with_significant_figures(400)
                 .unwrap_err()
                 .to_string(),
-            "Error: Unit cancellation not supported"
+            "Error: significant figures must be from 1 to 17"
+        );
+
+        let options = ConversionOptions::default().with_decimals(20).unwrap();
+        assert_eq!(
+            convert_units_with_options("1 ft", "ft", &options).unwrap(),
+            "1.00000000000000000000 ft"
+        );
+        assert_eq!(
+            ConversionOptions::default()
+                .with_decimals(65536)
+                .unwrap_err()
+                .to_string(),
+            "Error: decimals must be from 0 to 20"
+        );
+        // Options built without the builders are checked too
+        let options = ConversionOptions {
+            precision: Precision::Decimals(u32::MAX),
+            ..Default::default()
+        };
+        assert_eq!(
+            convert_units_with_options("1 ft", "ft", &options).unwrap_err(),
+            ConversionError::PrecisionOutOfRange {
+                setting: "decimals".to_string(),
+                min: 0,
+                max: 20
+            }
         );
     }
 
     #[test]
-    #[ignore = "not implemented"]
-    fn test_parentheses_in_expressions() {
+    fn test_results_out_of_range() {
         assert_eq!(
-            convert_units("60 miles / (1 hour)", "meters / second").unwrap(),
-            "26.8224 meters / second"
+            convert_units("1e400 m", "m").unwrap_err().to_string(),
+            "Error: Value out of range"
         );
         assert_eq!(
-            convert_units("(10 kilograms) / (2 meters)^3", "pounds / cubic foot").unwrap(),
-            "0.0780194 pounds / cubic foot"
+            convert_units("1e308 light years", "m").unwrap_err(),
+            ConversionError::ValueOutOfRange
         );
+        let options = ConversionOptions::default()
+            .with_notation("scientific")
+            .unwrap();
         assert_eq!(
-            convert_units("5 * (meters / second)", "feet / second").unwrap(),
-            "16.4042 feet / second"
+            convert_units_with_options("1e400 m", "km", &options).unwrap_err(),
+            ConversionError::ValueOutOfRange
+        );
+    }
+
+    fn with_rates(as_of: chrono::DateTime<chrono::Utc>) -> ConversionOptions {
+        let rates = RateTable::new("USD", as_of)
+            .with_rate("EUR", 0.8)
+            .with_rate("GBP", 0.75)
+            .with_rate("JPY", 150.0);
+        ConversionOptions {
+            exchange_rates: Some(Arc::new(rates)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_currency_conversion() {
+        let as_of = chrono::Utc::now() - chrono::TimeDelta::hours(3);
+        let options = with_rates(as_of);
+        let convert = |input: &str, output: &str| {
+            convert_units_with_options(input, output, &options)
+                .unwrap()
+                .replace(&currency::format_as_of(as_of), "<as of>")
+        };
+        assert_eq!(convert("100 USD", "EUR"), "80 EUR (rates as of <as of>)");
+        assert_eq!(convert("$100", "€"), "80 € (rates as of <as of>)");
+        assert_eq!(
+            convert("10 £", "euros"),
+            "10.6667 euros (rates as of <as of>)"
+        );
+        assert_eq!(
+            convert("1500 yen", "US dollars"),
+            "10 US dollars (rates as of <as of>)"
+        );
+        assert_eq!(convert("20 eur", "gbp"), "18.75 gbp (rates as of <as of>)");
+        // Currencies combine with other units like anything else
+        assert_eq!(
+            convert("4 USD/kg", "EUR/lb"),
+            "1.4515 EUR/lb (rates as of <as of>)"
+        );
+        assert_eq!(
+            convert("50 GBP/h", "$/min"),
+            "1.11111 $/min (rates as of <as of>)"
+        );
+
+        assert_eq!(
+            RateTable::from_json(
+                r#"{"base": "USD", "as_of": "2026-10-16T16:00:00Z", "rates": {"EUR": 0.8}}"#
+            )
+            .unwrap(),
+            RateTable::new("USD", "2026-10-16T16:00:00Z".parse().unwrap()).with_rate("EUR", 0.8)
+        );
+        assert_eq!(
+            RateTable::from_json(
+                r#"{"base": "usd", "as_of": "2026-10-16T16:00:00Z", "rates": {"eur": 0.8}}"#
+            )
+            .unwrap(),
+            RateTable::new("USD", "2026-10-16T16:00:00Z".parse().unwrap()).with_rate("EUR", 0.8)
+        );
+    }
+
+    #[test]
+    fn test_exchange_rate_errors() {
+        assert_eq!(
+            convert_units("100 USD", "EUR").unwrap_err().to_string(),
+            "Error: No exchange rates are loaded"
+        );
+
+        let options = with_rates(chrono::Utc::now());
+        assert_eq!(
+            convert_units_with_options("100 USD", "CHF", &options)
+                .unwrap_err()
+                .to_string(),
+            "Error: No exchange rate for 'CHF'"
+        );
+        assert_eq!(
+            convert_units_with_options("100 USD", "kg", &options)
+                .unwrap_err()
+                .to_string(),
+            "Error: Cannot convert from currency to mass"
+        );
+
+        let as_of = "2026-01-02T16:00:00Z".parse().unwrap();
+        assert_eq!(
+            convert_units_with_options("100 USD", "EUR", &with_rates(as_of))
+                .unwrap_err()
+                .to_string(),
+            "Error: Exchange rates are out of date (as of 2026-01-02 16:00 UTC)"
         );
     }
 }
@@ -0,0 +1,135 @@
+use super::dimension::{Base, Dimension};
+use super::registry::{self, Prefixes, Scale};
+
+/// Which units an `auto` output picks from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum System {
+    Metric,
+    Imperial,
+}
+
+impl System {
+    /// Reads an output unit of `auto`, `auto metric` or `auto imperial`. Plain `auto`
+    /// is metric.
+    pub fn from_spec(spec: &str) -> Option<Self> {
+        match spec.to_lowercase().split_whitespace().collect::<Vec<_>>()[..] {
+            ["auto"] | ["auto", "metric" | "si"] => Some(System::Metric),
+            ["auto", "imperial" | "us" | "customary"] => Some(System::Imperial),
+            _ => None,
+        }
+    }
+}
+
+/// The spellings `dimension` can be shown in, in no particular order: the units the
+/// registry offers in `system`, with areas also in the squares of its lengths. Where
+/// imperial has no units of a kind, like for time or data, it uses the metric ones.
+/// Data measured with binary prefixes, as in GiB, stays in binary prefixes. Empty when
+/// there's nothing sensible to pick from, as for currencies.
+pub(super) fn candidates(dimension: Dimension, system: System, binary: bool) -> Vec<String> {
+    let shown = |dimension: Dimension, system: System| -> Vec<String> {
+        registry::UNITS
+            .iter()
+            .filter(|def| def.dimension == dimension)
+            .filter_map(|def| match def.display {
+                Some((shown_in, spellings)) if shown_in == system => Some((def, spellings)),
+                _ => None,
+            })
+            .flat_map(|(def, spellings)| match def.symbols.first() {
+                Some(symbol) if binary && def.prefixes == Prefixes::Data => {
+                    binary_spellings(symbol)
+                }
+                _ => spellings
+                    .iter()
+                    .map(|spelling| spelling.to_string())
+                    .collect(),
+            })
+            .collect()
+    };
+    let in_system = |system| {
+        let mut spellings = shown(dimension, system);
+        if dimension == Dimension::AREA {
+            spellings.extend(
+                shown(Dimension::LENGTH, system)
+                    .into_iter()
+                    .map(|length| format!("{length}²")),
+            );
+        }
+        spellings
+    };
+
+    let mut candidates = in_system(system);
+    if candidates.is_empty() {
+        candidates = in_system(System::Metric);
+    }
+    if candidates.is_empty() {
+        candidates = coherent(dimension, system);
+    }
+    candidates
+}
+
+/// The unit with every binary prefix, as in `B`, `KiB`, `MiB`...
+fn binary_spellings(symbol: &str) -> Vec<String> {
+    registry::BINARY_PREFIXES
+        .iter()
+        .map(|prefix| format!("{}{symbol}", prefix.symbols[0]))
+        .chain([symbol.to_string()])
+        .collect()
+}
+
+/// The coherent SI unit, with a prefix from pico to tera, for dimensions like current
+/// that the registry doesn't offer units for. Compounds with no unit of their own, like
+/// acceleration, are spelled out from the base units of `system`, as in `ft/s²`.
+fn coherent(dimension: Dimension, system: System) -> Vec<String> {
+    if let Some(unit) = registry::UNITS.iter().find(|def| {
+        def.dimension == dimension
+            && def.scale == Scale::Linear(1.0)
+            && def.prefixes == Prefixes::Si
+    }) && let Some(symbol) = unit.symbols.first()
+    {
+        return registry::SI_PREFIXES
+            .iter()
+            .filter(|prefix| (1e-12..=1e12).contains(&prefix.factor))
+            .filter(|prefix| prefix.factor.log10().round() as i32 % 3 == 0)
+            .map(|prefix| format!("{}{symbol}", prefix.symbols[0]))
+            .chain([symbol.to_string()])
+            .collect();
+    }
+
+    let mut numerator = Vec::new();
+    let mut denominator = Vec::new();
+    for (base, exponent) in dimension.exponents() {
+        let symbol = match (base, system) {
+            (Base::Length, System::Metric) => "m",
+            (Base::Length, System::Imperial) => "ft",
+            (Base::Mass, System::Metric) => "kg",
+            (Base::Mass, System::Imperial) => "lb",
+            (Base::Time, _) => "s",
+            (Base::Temperature, _) => "K",
+            (Base::Information, _) => "b",
+            (Base::Current, _) => "A",
+            (Base::Angle, _) => "rad",
+            (Base::Currency, _) => return Vec::new(),
+        };
+        let term = match exponent.abs() {
+            1 => symbol.to_string(),
+            2 => format!("{symbol}²"),
+            3 => format!("{symbol}³"),
+            power => format!("{symbol}^{power}"),
+        };
+        if exponent > 0 {
+            numerator.push(term);
+        } else {
+            denominator.push(term);
+        }
+    }
+    // A bare reciprocal, as in `1/m²`, isn't a unit that reads back
+    if numerator.is_empty() {
+        return Vec::new();
+    }
+    let numerator = numerator.join("·");
+    match denominator.len() {
+        0 => vec![numerator],
+        1 => vec![format!("{numerator}/{}", denominator[0])],
+        _ => vec![format!("{numerator}/({})", denominator.join("·"))],
+    }
+}
@@ -0,0 +1,143 @@
+use super::ConversionError;
+
+/// The compact duration components, largest first.
+const COMPACT_UNITS: [(char, f64); 4] = [('d', 86_400.0), ('h', 3_600.0), ('m', 60.0), ('s', 1.0)];
+
+/// Reads a clock-style duration like `1:30:00` or `1h 30m 15s` as a number of seconds.
+/// Anything else, including a single compact component like `30m` (which stays thirty
+/// meters) and spaced-out quantities like `2 m 30 s`, is left for the expression parser.
+pub(super) fn parse(input: &str) -> Option<f64> {
+    let input = input.trim();
+    let (sign, input) = match input.strip_prefix(['-', '−']) {
+        Some(rest) => (-1.0, rest.trim_start()),
+        None => (1.0, input),
+    };
+    parse_clock(input)
+        .or_else(|| parse_compact(input))
+        .map(|seconds| sign * seconds)
+}
+
+// h:mm or h:mm:ss, where the seconds may have a fractional part
+fn parse_clock(input: &str) -> Option<f64> {
+    let parts: Vec<&str> = input.split(':').collect();
+    if !(2..=3).contains(&parts.len()) {
+        return None;
+    }
+
+    let (_, hours) = clock_field(parts[0], false)?;
+    let (len, minutes) = clock_field(parts[1], false)?;
+    if len != 2 || minutes >= 60.0 {
+        return None;
+    }
+    let seconds = match parts.get(2) {
+        Some(part) => {
+            let (len, seconds) = clock_field(part, true)?;
+            if len != 2 || seconds >= 60.0 {
+                return None;
+            }
+            seconds
+        }
+        None => 0.0,
+    };
+
+    Some(hours * 3_600.0 + minutes * 60.0 + seconds)
+}
+
+/// A clock field and the number of whole digits it was written with. Only the seconds
+/// may have a fractional part.
+fn clock_field(part: &str, fraction: bool) -> Option<(usize, f64)> {
+    let whole = match part.split_once('.') {
+        Some((whole, _)) if fraction => whole,
+        _ => part,
+    };
+    if whole.is_empty() || !whole.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    Some((whole.len(), part.parse().ok()?))
+}
+
+// Two or more of `<number>d`, `<number>h`, `<number>m`, `<number>s`, largest first. The
+// letter has to follow its number directly: written apart, `2 m 30 s` is an ordinary sum
+// of quantities, where `m` is a meter.
+fn parse_compact(input: &str) -> Option<f64> {
+    let mut rest = input;
+    let mut total = 0.0;
+    let mut parts = 0;
+    let mut next_unit = 0;
+
+    while !rest.is_empty() {
+        let end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let value: f64 = rest[..end].parse().ok()?;
+        let after = &rest[end..];
+        let letter = after.chars().next()?.to_ascii_lowercase();
+        let index = next_unit
+            + COMPACT_UNITS[next_unit..]
+                .iter()
+                .position(|&(unit, _)| unit == letter)?;
+
+        total += value * COMPACT_UNITS[index].1;
+        parts += 1;
+        next_unit = index + 1;
+        rest = after[1..].trim_start();
+    }
+
+    (parts >= 2).then_some(total)
+}
+
+/// A clock-style output unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ClockFormat {
+    /// `1:30:15`
+    Colon,
+    /// `1h 30m 15s`
+    Compact,
+}
+
+impl ClockFormat {
+    pub fn from_spec(spec: &str) -> Option<Self> {
+        match spec.to_lowercase().as_str() {
+            "h:m:s" | "h:mm:ss" | "hh:mm:ss" => Some(ClockFormat::Colon),
+            "h m s" | "hms" => Some(ClockFormat::Compact),
+            _ => None,
+        }
+    }
+
+    /// Formats a duration in seconds, to the millisecond. Rounding happens before the
+    /// duration is split up, so 59.9999 seconds shows as `0:01:00` rather than `0:00:60`.
+    /// Durations too long to count in milliseconds are an error.
+    pub fn format(self, seconds: f64) -> Result<String, ConversionError> {
+        let sign = if seconds < 0.0 { "-" } else { "" };
+        let millis = (seconds.abs() * 1000.0).round();
+        // 2^64 itself is the first value that doesn't fit
+        if !millis.is_finite() || millis >= u64::MAX as f64 {
+            return Err(ConversionError::ValueOutOfRange);
+        }
+        let millis = millis as u64;
+        let (hours, minutes) = (millis / 3_600_000, millis / 60_000 % 60);
+        let (whole, fraction) = (millis / 1000 % 60, millis % 1000);
+        let fraction = if fraction == 0 {
+            String::new()
+        } else {
+            format!(".{fraction:03}").trim_end_matches('0').to_string()
+        };
+
+        Ok(match self {
+            ClockFormat::Colon => format!("{sign}{hours}:{minutes:02}:{whole:02}{fraction}"),
+            ClockFormat::Compact => {
+                let mut parts = Vec::new();
+                if hours > 0 {
+                    parts.push(format!("{hours}h"));
+                }
+                if minutes > 0 {
+                    parts.push(format!("{minutes}m"));
+                }
+                if whole > 0 || !fraction.is_empty() || parts.is_empty() {
+                    parts.push(format!("{whole}{fraction}s"));
+                }
+                format!("{sign}{}", parts.join(" "))
+            }
+        })
+    }
+}
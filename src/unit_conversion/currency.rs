@@ -0,0 +1,76 @@
+use super::{ConversionError, ConversionOptions};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+/// Exchange rates against a single base currency, as published at one point in time.
+/// Tables are usually loaded by a `RateProvider`, but can be built by hand too.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct RateTable {
+    /// The ISO 4217 code every rate is quoted against
+    pub base: String,
+    /// When the rates were published
+    pub as_of: DateTime<Utc>,
+    /// How much of each currency one unit of the base currency buys, by ISO 4217 code
+    pub rates: HashMap<String, f64>,
+}
+
+impl RateTable {
+    pub fn new(base: &str, as_of: DateTime<Utc>) -> Self {
+        Self {
+            base: base.to_ascii_uppercase(),
+            as_of,
+            rates: HashMap::new(),
+        }
+    }
+
+    pub fn with_rate(mut self, code: &str, rate: f64) -> Self {
+        self.rates.insert(code.to_ascii_uppercase(), rate);
+        self
+    }
+
+    /// Reads a table written as JSON, like
+    /// `{"base": "USD", "as_of": "2026-10-16T16:00:00Z", "rates": {"EUR": 0.92}}`. Codes
+    /// are uppercased, as they are by [`RateTable::new`] and [`RateTable::with_rate`].
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let table: Self = serde_json::from_str(json)?;
+        Ok(Self {
+            base: table.base.to_ascii_uppercase(),
+            rates: table
+                .rates
+                .into_iter()
+                .map(|(code, rate)| (code.to_ascii_uppercase(), rate))
+                .collect(),
+            ..table
+        })
+    }
+
+    /// The worth of one unit of `code` in the base currency.
+    fn value_of(&self, code: &str) -> Option<f64> {
+        if code == self.base {
+            return Some(1.0);
+        }
+        self.rates
+            .get(code)
+            .filter(|rate| rate.is_finite() && **rate > 0.0)
+            .map(|rate| 1.0 / rate)
+    }
+}
+
+/// The worth of one unit of the currency `code` under the exchange rates in `options`.
+pub(super) fn value_of(code: &str, options: &ConversionOptions) -> Result<f64, ConversionError> {
+    let rates = options
+        .exchange_rates
+        .as_deref()
+        .ok_or(ConversionError::NoExchangeRates)?;
+    if Utc::now() - rates.as_of > options.max_rate_age {
+        return Err(ConversionError::StaleExchangeRates { as_of: rates.as_of });
+    }
+    rates
+        .value_of(code)
+        .ok_or_else(|| ConversionError::MissingExchangeRate(code.to_string()))
+}
+
+/// How a rate table's timestamp is shown in results and errors.
+pub(super) fn format_as_of(as_of: DateTime<Utc>) -> String {
+    as_of.format("%Y-%m-%d %H:%M UTC").to_string()
+}
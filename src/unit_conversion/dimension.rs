@@ -0,0 +1,161 @@
+use super::ConversionError;
+
+/// The base dimensions a unit can be built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Base {
+    Length,
+    Mass,
+    Time,
+    Temperature,
+    /// Digital information, counted in bits
+    Information,
+    Current,
+    /// Plane angle. Radians are tracked rather than treated as dimensionless, so an angle
+    /// never converts to a plain number or a length by accident.
+    Angle,
+    /// Money, in the base currency of the loaded exchange rate table
+    Currency,
+}
+
+const BASE_COUNT: usize = 8;
+
+impl Base {
+    const ALL: [Base; BASE_COUNT] = [
+        Base::Length,
+        Base::Mass,
+        Base::Time,
+        Base::Temperature,
+        Base::Information,
+        Base::Current,
+        Base::Angle,
+        Base::Currency,
+    ];
+}
+
+/// A physical dimension as a vector of exponents over the [`Base`] dimensions, so velocity
+/// is `Length^1 * Time^-1` and force is `Mass^1 * Length^1 * Time^-2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(super) struct Dimension([i8; BASE_COUNT]);
+
+impl Dimension {
+    pub const NONE: Dimension = Dimension([0; BASE_COUNT]);
+    pub const LENGTH: Dimension = Dimension::base(Base::Length);
+    pub const MASS: Dimension = Dimension::base(Base::Mass);
+    pub const TIME: Dimension = Dimension::base(Base::Time);
+    pub const TEMPERATURE: Dimension = Dimension::base(Base::Temperature);
+    pub const INFORMATION: Dimension = Dimension::base(Base::Information);
+    pub const CURRENT: Dimension = Dimension::base(Base::Current);
+    pub const ANGLE: Dimension = Dimension::base(Base::Angle);
+    pub const CURRENCY: Dimension = Dimension::base(Base::Currency);
+
+    pub const AREA: Dimension = Self::LENGTH.power(2);
+    pub const VOLUME: Dimension = Self::LENGTH.power(3);
+    pub const VELOCITY: Dimension = Self::LENGTH.quotient(Self::TIME);
+    pub const ACCELERATION: Dimension = Self::VELOCITY.quotient(Self::TIME);
+    pub const MASS_DENSITY: Dimension = Self::MASS.quotient(Self::VOLUME);
+    pub const FORCE: Dimension = Self::MASS.product(Self::ACCELERATION);
+    pub const ENERGY: Dimension = Self::FORCE.product(Self::LENGTH);
+    pub const POWER: Dimension = Self::ENERGY.quotient(Self::TIME);
+    pub const PRESSURE: Dimension = Self::FORCE.quotient(Self::AREA);
+    /// Distance travelled per volume of fuel burned
+    pub const FUEL_ECONOMY: Dimension = Self::LENGTH.quotient(Self::VOLUME);
+    pub const VOLUMETRIC_FLOW: Dimension = Self::VOLUME.quotient(Self::TIME);
+    pub const MASS_FLOW: Dimension = Self::MASS.quotient(Self::TIME);
+    pub const DYNAMIC_VISCOSITY: Dimension = Self::PRESSURE.product(Self::TIME);
+    pub const KINEMATIC_VISCOSITY: Dimension = Self::AREA.quotient(Self::TIME);
+    pub const DATA_RATE: Dimension = Self::INFORMATION.quotient(Self::TIME);
+    pub const FREQUENCY: Dimension = Self::TIME.power(-1);
+    pub const ANGULAR_VELOCITY: Dimension = Self::ANGLE.quotient(Self::TIME);
+    pub const CHARGE: Dimension = Self::CURRENT.product(Self::TIME);
+    pub const VOLTAGE: Dimension = Self::POWER.quotient(Self::CURRENT);
+    pub const RESISTANCE: Dimension = Self::VOLTAGE.quotient(Self::CURRENT);
+    pub const CONDUCTANCE: Dimension = Self::RESISTANCE.power(-1);
+    pub const CAPACITANCE: Dimension = Self::CHARGE.quotient(Self::VOLTAGE);
+    pub const MAGNETIC_FLUX: Dimension = Self::VOLTAGE.product(Self::TIME);
+    pub const INDUCTANCE: Dimension = Self::MAGNETIC_FLUX.quotient(Self::CURRENT);
+    pub const MAGNETIC_FLUX_DENSITY: Dimension = Self::MAGNETIC_FLUX.quotient(Self::AREA);
+
+    pub const fn base(base: Base) -> Dimension {
+        let mut exponents = [0; BASE_COUNT];
+        exponents[base as usize] = 1;
+        Dimension(exponents)
+    }
+
+    /// `self * other`, or an error if an exponent leaves the range of `i8`, as in
+    /// `m^127 * m`.
+    pub fn times(self, other: Dimension) -> Result<Dimension, ConversionError> {
+        self.checked_times(other)
+            .ok_or(ConversionError::ExponentOutOfRange)
+    }
+
+    /// `self ^ power`, or an error if an exponent leaves the range of `i8`, as in
+    /// `(m^100)^2`.
+    pub fn powi(self, power: i8) -> Result<Dimension, ConversionError> {
+        self.checked_powi(power)
+            .ok_or(ConversionError::ExponentOutOfRange)
+    }
+
+    const fn checked_times(self, other: Dimension) -> Option<Dimension> {
+        let mut exponents = self.0;
+        let mut i = 0;
+        while i < BASE_COUNT {
+            exponents[i] = match exponents[i].checked_add(other.0[i]) {
+                Some(exponent) => exponent,
+                None => return None,
+            };
+            i += 1;
+        }
+        Some(Dimension(exponents))
+    }
+
+    const fn checked_powi(self, power: i8) -> Option<Dimension> {
+        let mut exponents = self.0;
+        let mut i = 0;
+        while i < BASE_COUNT {
+            exponents[i] = match exponents[i].checked_mul(power) {
+                Some(exponent) => exponent,
+                None => return None,
+            };
+            i += 1;
+        }
+        Some(Dimension(exponents))
+    }
+
+    // The constants above are built with these, where an overflow fails the build
+
+    const fn product(self, other: Dimension) -> Dimension {
+        match self.checked_times(other) {
+            Some(dimension) => dimension,
+            None => panic!("exponent out of range"),
+        }
+    }
+
+    const fn quotient(self, other: Dimension) -> Dimension {
+        self.product(other.power(-1))
+    }
+
+    const fn power(self, power: i8) -> Dimension {
+        match self.checked_powi(power) {
+            Some(dimension) => dimension,
+            None => panic!("exponent out of range"),
+        }
+    }
+
+    pub fn is_dimensionless(self) -> bool {
+        self == Self::NONE
+    }
+
+    /// Whether `base` appears in the dimension at all, as currency does in `USD/kg`.
+    pub fn involves(self, base: Base) -> bool {
+        self.0[base as usize] != 0
+    }
+
+    /// The bases the dimension is built from, with their exponents, as `(Mass, 1)` and
+    /// `(Time, -1)` for a mass flow.
+    pub fn exponents(self) -> impl Iterator<Item = (Base, i8)> {
+        Base::ALL
+            .into_iter()
+            .map(move |base| (base, self.0[base as usize]))
+            .filter(|&(_, exponent)| exponent != 0)
+    }
+}
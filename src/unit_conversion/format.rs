@@ -0,0 +1,252 @@
+use super::{ConversionError, ConversionOptions};
+
+/// The most significant figures a result can be shown with, as many as an `f64` holds.
+const MAX_SIGNIFICANT_FIGURES: u32 = 17;
+
+/// The most decimal places a result can be shown with.
+const MAX_DECIMALS: u32 = 20;
+
+/// How many digits a result is shown with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// Six significant figures, or two decimals from 1000 up, with trailing zeros
+    /// trimmed
+    #[default]
+    Standard,
+    /// This many significant figures, keeping trailing zeros: `1.50 m` to three. From 1
+    /// to 17.
+    SignificantFigures(u32),
+    /// This many decimal places, keeping trailing zeros: `1.50 m` to two. Up to 20.
+    Decimals(u32),
+}
+
+impl Precision {
+    /// `self`, if it's within the digits a result can sensibly be shown with.
+    pub(super) fn check(self) -> Result<Self, ConversionError> {
+        let (setting, min, max) = match self {
+            Precision::Standard => return Ok(self),
+            Precision::SignificantFigures(figures)
+                if (1..=MAX_SIGNIFICANT_FIGURES).contains(&figures) =>
+            {
+                return Ok(self);
+            }
+            Precision::Decimals(decimals) if decimals <= MAX_DECIMALS => return Ok(self),
+            Precision::SignificantFigures(_) => ("significant figures", 1, MAX_SIGNIFICANT_FIGURES),
+            Precision::Decimals(_) => ("decimals", 0, MAX_DECIMALS),
+        };
+        Err(ConversionError::PrecisionOutOfRange {
+            setting: setting.to_string(),
+            min: min as usize,
+            max: max as usize,
+        })
+    }
+}
+
+/// Which way a result is rounded when it has more digits than its [`Precision`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Halves go to the even neighbour, so 0.125 is 0.12 and 0.375 is 0.38
+    #[default]
+    HalfEven,
+    /// Halves go away from zero, so 0.125 is 0.13
+    HalfUp,
+    /// Extra digits are dropped, so 0.129 is 0.12
+    Truncate,
+}
+
+impl std::str::FromStr for RoundingMode {
+    type Err = ConversionError;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "half-even" | "bankers" => Ok(RoundingMode::HalfEven),
+            "half-up" => Ok(RoundingMode::HalfUp),
+            "truncate" | "toward-zero" => Ok(RoundingMode::Truncate),
+            _ => Err(ConversionError::UnknownRoundingMode(mode.to_string())),
+        }
+    }
+}
+
+/// When a result is shown in E notation, like `9.46073e15 m`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Notation {
+    /// Only from 10^15 up
+    #[default]
+    Standard,
+    /// Below 10^-3 and from 10^6 up, so tiny values don't round away to zero
+    Auto,
+    /// Always, apart from zero
+    Scientific,
+}
+
+impl std::str::FromStr for Notation {
+    type Err = ConversionError;
+
+    fn from_str(notation: &str) -> Result<Self, Self::Err> {
+        match notation.trim().to_lowercase().as_str() {
+            "standard" | "default" => Ok(Notation::Standard),
+            "auto" | "automatic" => Ok(Notation::Auto),
+            "scientific" | "e" => Ok(Notation::Scientific),
+            _ => Err(ConversionError::UnknownNotation(notation.to_string())),
+        }
+    }
+}
+
+/// Writes out a result's value under the precision, rounding and notation in `options`.
+pub(super) fn number(value: f64, options: &ConversionOptions) -> String {
+    // Rounding to significant figures can carry into a new leading digit, as 999.96 does
+    // to 1000 at four figures, so it comes before anything looks at the value's size
+    let value = match options.precision {
+        Precision::SignificantFigures(figures) => round(
+            value,
+            figures as i32 - 1 - exponent(value),
+            options.rounding,
+        ),
+        _ => value,
+    };
+    let magnitude = value.abs();
+    let scientific = match options.notation {
+        Notation::Standard => magnitude >= 1e15,
+        Notation::Auto => value != 0.0 && !(1e-3..1e6).contains(&magnitude),
+        Notation::Scientific => value != 0.0,
+    };
+    if scientific {
+        return scientific_number(value, options);
+    }
+
+    match options.precision {
+        Precision::Standard => {
+            if value == 0.0 {
+                return "0".to_string();
+            }
+            // Within floating point error of 1 is just 1
+            if (value - 1.0).abs() < 5e-6 {
+                return "1".to_string();
+            }
+            let decimals = if magnitude >= 1000.0 {
+                2
+            } else if magnitude >= 1.0 {
+                let int_digits = (magnitude.log10().floor() + 1.0) as i32;
+                6 - int_digits
+            } else {
+                6
+            };
+            trim(&fixed(value, decimals, options.rounding)).to_string()
+        }
+        Precision::Decimals(decimals) => {
+            fixed(value, decimals.min(MAX_DECIMALS) as i32, options.rounding)
+        }
+        Precision::SignificantFigures(figures) => {
+            let figures = figures.clamp(1, MAX_SIGNIFICANT_FIGURES);
+            let decimals = figures as i32 - 1 - exponent(value);
+            fixed(value, decimals, options.rounding)
+        }
+    }
+}
+
+/// `value` in E notation, with the precision applying to the mantissa.
+fn scientific_number(value: f64, options: &ConversionOptions) -> String {
+    let mantissa_decimals = match options.precision {
+        Precision::Standard => 5,
+        Precision::SignificantFigures(figures) => figures.clamp(1, MAX_SIGNIFICANT_FIGURES) - 1,
+        Precision::Decimals(decimals) => decimals.min(MAX_DECIMALS),
+    } as usize;
+    let rounded = round(
+        value,
+        mantissa_decimals as i32 - exponent(value),
+        options.rounding,
+    );
+    let formatted = format!("{rounded:e}");
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let mantissa = match options.precision {
+        Precision::Standard => mantissa.to_string(),
+        _ => pad(mantissa, mantissa_decimals),
+    };
+    format!("{mantissa}e{exponent}")
+}
+
+/// `value` rounded to `decimals` places and written with that many, or none if
+/// `decimals` is negative and the rounding falls left of the decimal point.
+fn fixed(value: f64, decimals: i32, mode: RoundingMode) -> String {
+    let rounded = round(value, decimals, mode);
+    pad(&rounded.to_string(), decimals.max(0) as usize)
+}
+
+/// Pads a number written in its shortest form with zeros to `places` decimal places.
+/// Padding the shortest form rather than printing that many places keeps the digits
+/// past what an `f64` holds from showing, as in `0.10000000000000001`.
+fn pad(number: &str, places: usize) -> String {
+    let shown = number
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    let mut padded = number.to_string();
+    if shown < places {
+        if shown == 0 {
+            padded.push('.');
+        }
+        padded.extend(std::iter::repeat_n('0', places - shown));
+    }
+    padded
+}
+
+/// Drops trailing zeros after the decimal point, and the point itself if nothing's left.
+fn trim(number: &str) -> &str {
+    if number.contains('.') {
+        number.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        number
+    }
+}
+
+/// The power of ten of the leading digit, as in 2 for 123.4.
+fn exponent(value: f64) -> i32 {
+    let formatted = format!("{:e}", value.abs());
+    formatted
+        .split_once('e')
+        .and_then(|(_, exponent)| exponent.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Rounds `value` to `decimals` places, or to tens, hundreds and so on when `decimals`
+/// is negative. Rounding works on the shortest decimal form of the value, the one it's
+/// printed as, so 2.675 rounds half up to 2.68 even though the nearest double is a
+/// little below it.
+fn round(value: f64, decimals: i32, mode: RoundingMode) -> f64 {
+    if !value.is_finite() || value == 0.0 {
+        return value;
+    }
+
+    let formatted = format!("{:e}", value.abs());
+    let (mantissa, exponent) = formatted.split_once('e').unwrap_or((&formatted, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let digits: Vec<u8> = mantissa.bytes().filter(u8::is_ascii_digit).collect();
+
+    // How many of the significant digits are kept
+    let kept = exponent + 1 + decimals;
+    if kept >= digits.len() as i32 {
+        return value;
+    }
+    let (kept_digits, dropped) = digits.split_at(kept.max(0) as usize);
+    let round_up = match mode {
+        RoundingMode::Truncate => false,
+        // Everything kept lies left of the first digit, so only a carry can be left over
+        _ if kept < 0 => false,
+        RoundingMode::HalfUp => dropped[0] >= b'5',
+        RoundingMode::HalfEven => {
+            let odd = kept_digits.last().is_some_and(|digit| digit % 2 == 1);
+            dropped[0] > b'5'
+                || dropped[0] == b'5' && (odd || dropped[1..].iter().any(|&digit| digit != b'0'))
+        }
+    };
+
+    let mut whole: u128 = kept_digits
+        .iter()
+        .fold(0, |whole, digit| whole * 10 + u128::from(digit - b'0'));
+    if round_up {
+        whole += 1;
+    }
+    let rounded: f64 = format!("{whole}e{}", -decimals)
+        .parse()
+        .unwrap_or(value.abs());
+    rounded.copysign(value)
+}
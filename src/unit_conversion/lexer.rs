@@ -0,0 +1,134 @@
+use super::ConversionError;
+use super::number;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum TokenKind<'a> {
+    Number(f64),
+    Word(&'a str),
+    Star,
+    Slash,
+    Caret,
+    Minus,
+    Open,
+    Close,
+    Superscript(i8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct Token<'a> {
+    pub kind: TokenKind<'a>,
+    /// Byte offset of the token in the input, for error messages
+    pub position: usize,
+}
+
+fn is_word_char(c: char) -> bool {
+    // The degree sign, the one-character temperature symbols, the increment sign, the
+    // primes for arcminutes and arcseconds and the apothecaries' signs belong to the
+    // unit, as in `°C`, `℉`, `∆°F`, `30′` and `2℈`
+    c.is_alphabetic()
+        || matches!(c, '_' | '°' | '℃' | '℉' | '∆' | '′' | '″' | '℈' | '℥')
+        || is_currency_sign(c)
+}
+
+/// Currency signs are words too, so `$5`, `5 €` and `R$` all read as a currency.
+fn is_currency_sign(c: char) -> bool {
+    matches!(
+        c,
+        '$' | '€'
+            | '£'
+            | '¥'
+            | '₹'
+            | '₩'
+            | '₽'
+            | '₺'
+            | '₪'
+            | '฿'
+            | '₱'
+            | '₴'
+            | '₦'
+            | '₫'
+    )
+}
+
+/// Splits a quantity expression like `(10 m)*(5 m)` or `60 miles per hour` into tokens.
+/// Numbers and words don't need whitespace between them, so `10m` is two tokens. See
+/// [`number::scan`] for the forms a number can take.
+pub(super) fn tokenize(input: &str) -> Result<Vec<Token<'_>>, ConversionError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+
+    while let Some(&(position, c)) = chars.peek() {
+        let kind = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '*' | '·' | '×' => TokenKind::Star,
+            '/' => TokenKind::Slash,
+            '^' => TokenKind::Caret,
+            '-' | '−' => TokenKind::Minus,
+            '(' => TokenKind::Open,
+            ')' => TokenKind::Close,
+            // Quote marks for feet and inches stand alone, so `5'11"` is four tokens
+            '\'' | '’' | '"' | '”' => {
+                TokenKind::Word(&input[position..position + c.len_utf8()])
+            }
+            '²' => TokenKind::Superscript(2),
+            '³' => TokenKind::Superscript(3),
+            c if number::starts_number(c) => {
+                let (number, len) = number::scan(&input[position..]).map_err(|len| {
+                    ConversionError::ParseError {
+                        position,
+                        message: format!("invalid number '{}'", &input[position..position + len]),
+                    }
+                })?;
+                while chars.peek().is_some_and(|&(i, _)| i < position + len) {
+                    chars.next();
+                }
+                tokens.push(Token {
+                    kind: TokenKind::Number(number),
+                    position,
+                });
+                continue;
+            }
+            c if is_word_char(c) => {
+                let mut end = position;
+                while let Some(&(i, c)) = chars.peek() {
+                    // Digits inside a word belong to it, as in `inH2O`, but trailing ones
+                    // don't: `m2` is still a meter followed by a number
+                    let inner_digits = c.is_ascii_digit()
+                        && input[i..]
+                            .trim_start_matches(|c: char| c.is_ascii_digit())
+                            .starts_with(is_word_char);
+                    // So does a hyphen between letters, as in `light-year`
+                    let hyphen = c == '-'
+                        && input[..i].ends_with(char::is_alphabetic)
+                        && input[i + 1..].starts_with(char::is_alphabetic);
+                    if !is_word_char(c) && !inner_digits && !hyphen {
+                        break;
+                    }
+                    end = i + c.len_utf8();
+                    chars.next();
+                }
+                tokens.push(Token {
+                    kind: match &input[position..end] {
+                        word if word.eq_ignore_ascii_case("per") => TokenKind::Slash,
+                        word => TokenKind::Word(word),
+                    },
+                    position,
+                });
+                continue;
+            }
+            c => {
+                return Err(ConversionError::ParseError {
+                    position,
+                    message: format!("unexpected character '{c}'"),
+                });
+            }
+        };
+        chars.next();
+        tokens.push(Token { kind, position });
+    }
+
+    Ok(tokens)
+}
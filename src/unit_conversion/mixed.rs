@@ -0,0 +1,68 @@
+use super::{ConversionError, get_plural_unit};
+
+/// The most decimal places the smallest part can be rounded to. Past this, the parts are
+/// counted in steps too fine to mean anything, and eventually too many for an `f64`.
+const MAX_DECIMALS: usize = 9;
+
+/// `decimals`, if the smallest part can be rounded to that many places.
+pub(super) fn check_decimals(decimals: usize) -> Result<usize, ConversionError> {
+    if decimals > MAX_DECIMALS {
+        return Err(ConversionError::PrecisionOutOfRange {
+            setting: "part decimals".to_string(),
+            min: 0,
+            max: MAX_DECIMALS,
+        });
+    }
+    Ok(decimals)
+}
+
+/// Splits a mixed-unit output spec like `ft+in`, `st+lb` or `h+min+s` into its units.
+/// `None` for anything without a `+`.
+pub(super) fn split_spec(spec: &str) -> Option<Vec<&str>> {
+    spec.contains('+')
+        .then(|| spec.split('+').map(str::trim).collect())
+}
+
+/// Formats `value`, in SI units, as a sum of `parts` like `5 ft 10.87 in`. Each part is
+/// a size in SI units and the spelling to show it with, and they're shown largest first
+/// whatever order they come in. Only the smallest part has decimals, rounded to
+/// `decimals` places before the value is split up, so 5 ft 11.999 in shows as `6 ft`
+/// rather than `5 ft 12 in`. Parts that come to zero are left out. `decimals` has been
+/// through [`check_decimals`].
+pub(super) fn format(value: f64, parts: &[(f64, String)], decimals: usize) -> String {
+    let mut parts: Vec<&(f64, String)> = parts.iter().collect();
+    parts.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let Some(((smallest, smallest_unit), larger)) = parts.split_last() else {
+        return String::new();
+    };
+
+    // Everything is counted in steps of the smallest part's last decimal place
+    let step = 10f64.powi(-(decimals as i32));
+    let total = (value.abs() / smallest / step).round();
+    let mut steps = total;
+    let mut shown = Vec::new();
+    for (size, unit) in larger {
+        let per = (size / smallest / step).round();
+        let count = (steps / per).floor();
+        steps -= count * per;
+        if count > 0.0 {
+            shown.push(format!("{count} {}", get_plural_unit(unit, count != 1.0)));
+        }
+    }
+
+    if steps > 0.0 || shown.is_empty() {
+        let formatted = format!("{:.decimals$}", steps * step);
+        let trimmed = if formatted.contains('.') {
+            formatted.trim_end_matches('0').trim_end_matches('.')
+        } else {
+            &formatted
+        };
+        shown.push(format!(
+            "{trimmed} {}",
+            get_plural_unit(smallest_unit, trimmed != "1")
+        ));
+    }
+
+    let sign = if value < 0.0 && total > 0.0 { "-" } else { "" };
+    format!("{sign}{}", shown.join(" "))
+}
@@ -0,0 +1,254 @@
+/// Unicode vulgar fractions, which can stand alone (`½ mile`) or follow a whole number
+/// (`1½ cups`, `2 ¾ in`).
+fn vulgar_fraction(c: char) -> Option<f64> {
+    Some(match c {
+        '½' => 1.0 / 2.0,
+        '⅓' => 1.0 / 3.0,
+        '⅔' => 2.0 / 3.0,
+        '¼' => 1.0 / 4.0,
+        '¾' => 3.0 / 4.0,
+        '⅕' => 1.0 / 5.0,
+        '⅖' => 2.0 / 5.0,
+        '⅗' => 3.0 / 5.0,
+        '⅘' => 4.0 / 5.0,
+        '⅙' => 1.0 / 6.0,
+        '⅚' => 5.0 / 6.0,
+        '⅐' => 1.0 / 7.0,
+        '⅛' => 1.0 / 8.0,
+        '⅜' => 3.0 / 8.0,
+        '⅝' => 5.0 / 8.0,
+        '⅞' => 7.0 / 8.0,
+        '⅑' => 1.0 / 9.0,
+        '⅒' => 1.0 / 10.0,
+        _ => return None,
+    })
+}
+
+fn superscript_digit(c: char) -> Option<char> {
+    let digit = match c {
+        '⁰' => '0',
+        '¹' => '1',
+        '²' => '2',
+        '³' => '3',
+        '⁴' => '4',
+        '⁵' => '5',
+        '⁶' => '6',
+        '⁷' => '7',
+        '⁸' => '8',
+        '⁹' => '9',
+        _ => return None,
+    };
+    Some(digit)
+}
+
+/// Whether a number starts with `c`.
+pub(super) fn starts_number(c: char) -> bool {
+    c.is_ascii_digit() || c == '.' || vulgar_fraction(c).is_some()
+}
+
+/// Reads the numeric literal at the start of `input`, and returns its value and how many
+/// bytes it took up. A literal is one of:
+///
+/// - a decimal, with optional `e` exponent: `1.5`, `.5`, `1e-3`
+/// - a whole number with thousands separators or underscores: `1,500`, `1_000_000`
+/// - a fraction or mixed number: `3/4`, `2 1/2`, `½`, `1½`, `2 ¾`
+/// - a decimal times a power of ten: `1.5×10^3`, `2 x 10^-6`, `3×10⁸`
+/// - a hexadecimal, octal or binary integer: `0xFF`, `0o17`, `0b1010`
+///
+/// On a malformed literal like `1.2.3` the error is the length of the bad text.
+pub(super) fn scan(input: &str) -> Result<(f64, usize), usize> {
+    let (value, rest) = radix_integer(input)
+        .or_else(|| vulgar(input))
+        .or_else(|| decimal(input).map(|(value, rest)| times_ten(value, rest)))
+        .ok_or_else(|| malformed(input))?;
+
+    // A second decimal point, or a stray digit after hex, means the whole thing is garbled
+    if rest.starts_with(['.', '_']) || rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return Err(malformed(input));
+    }
+    Ok((value, input.len() - rest.len()))
+}
+
+/// How far a malformed literal runs, for the error message.
+fn malformed(input: &str) -> usize {
+    input
+        .find(|c: char| !c.is_ascii_alphanumeric() && !matches!(c, '.' | '_' | ','))
+        .unwrap_or(input.len())
+}
+
+/// `0x`, `0o` and `0b` integers, for programmer input.
+fn radix_integer(input: &str) -> Option<(f64, &str)> {
+    let radix = match input.get(..2)? {
+        "0x" | "0X" => 16,
+        "0o" | "0O" => 8,
+        "0b" | "0B" => 2,
+        _ => return None,
+    };
+    let (digits, rest) = digits(&input[2..], radix, false);
+    let value = u64::from_str_radix(&digits, radix).ok()?;
+    Some((value as f64, rest))
+}
+
+/// A vulgar fraction on its own, as in `½ mile`.
+fn vulgar(input: &str) -> Option<(f64, &str)> {
+    let mut chars = input.chars();
+    let value = vulgar_fraction(chars.next()?)?;
+    Some((value, chars.as_str()))
+}
+
+/// A plain decimal, or a whole number followed by a fraction to make a mixed number.
+fn decimal(input: &str) -> Option<(f64, &str)> {
+    let (whole, rest) = digits(input, 10, true);
+    let (point, (fraction, rest)) = match rest.strip_prefix('.') {
+        Some(rest) => (true, digits(rest, 10, false)),
+        None => (false, (String::new(), rest)),
+    };
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+
+    if !point && let Some((fraction, rest)) = simple_fraction(rest) {
+        return Some((fraction.value(&whole), rest));
+    }
+
+    let (exponent, rest) = exponent(rest);
+    let text = format!("{whole}.{fraction}e{exponent}");
+    Some((text.parse().ok()?, rest))
+}
+
+/// What follows the whole number in a fraction or mixed number.
+enum Fraction {
+    /// `3/4`: the whole number was the numerator
+    Over(f64),
+    /// `2 1/2` or `1½`: the whole number comes before a proper fraction
+    Plus(f64),
+}
+
+impl Fraction {
+    fn value(&self, whole: &str) -> f64 {
+        let whole: f64 = whole.parse().unwrap_or(0.0);
+        match *self {
+            Fraction::Over(denominator) => whole / denominator,
+            Fraction::Plus(fraction) => whole + fraction,
+        }
+    }
+}
+
+fn simple_fraction(rest: &str) -> Option<(Fraction, &str)> {
+    // `3/4` has no spaces around the slash; `3 / 4 cup` is a division like any other
+    if let Some(after) = rest.strip_prefix(['/', '⁄']) {
+        let (denominator, after) = digits(after, 10, false);
+        let denominator: f64 = denominator.parse().ok()?;
+        if after.starts_with('.') || denominator == 0.0 {
+            return None;
+        }
+        return Some((Fraction::Over(denominator), after));
+    }
+
+    if let Some((fraction, after)) = vulgar(rest) {
+        return Some((Fraction::Plus(fraction), after));
+    }
+
+    // A mixed number: a whole number, a space, and a fraction
+    let after = rest.trim_start();
+    if after.len() == rest.len() {
+        return None;
+    }
+    if let Some((fraction, after)) = vulgar(after) {
+        return Some((Fraction::Plus(fraction), after));
+    }
+    let (numerator, after) = digits(after, 10, false);
+    let after = after.strip_prefix(['/', '⁄'])?;
+    let (denominator, after) = digits(after, 10, false);
+    let (numerator, denominator): (f64, f64) = (numerator.parse().ok()?, denominator.parse().ok()?);
+    if after.starts_with('.') || denominator == 0.0 || numerator >= denominator {
+        return None;
+    }
+    Some((Fraction::Plus(numerator / denominator), after))
+}
+
+/// An `e` exponent like `e-3`. The `e` only counts when digits follow it, so `5em`
+/// stays a number and a word.
+fn exponent(input: &str) -> (String, &str) {
+    let Some(after) = input.strip_prefix(['e', 'E']) else {
+        return ("0".to_string(), input);
+    };
+    let (sign, after) = match after.strip_prefix(['+', '-', '−']) {
+        Some(unsigned) if after.starts_with('+') => ("", unsigned),
+        Some(unsigned) => ("-", unsigned),
+        None => ("", after),
+    };
+    match digits(after, 10, false) {
+        (digits, _) if digits.is_empty() => ("0".to_string(), input),
+        (digits, rest) => (format!("{sign}{digits}"), rest),
+    }
+}
+
+/// Scientific notation written out, as in `1.5×10^3`, `1.5 x 10^-3` or `3×10⁸`. Left
+/// alone unless the whole `×10^n` is there, so `2 x` is still a number and a word.
+fn times_ten(value: f64, input: &str) -> (f64, &str) {
+    let power = || {
+        let after = input.trim_start().strip_prefix(['×', 'x'])?;
+        let after = after.trim_start().strip_prefix("10")?;
+        match after.strip_prefix('^') {
+            Some(after) => {
+                let (sign, after) = match after.strip_prefix(['-', '−']) {
+                    Some(after) => (-1, after),
+                    None => (1, after.strip_prefix('+').unwrap_or(after)),
+                };
+                let (digits, after) = digits(after, 10, false);
+                Some((sign * digits.parse::<i32>().ok()?, after))
+            }
+            None => {
+                let (sign, after) = match after.strip_prefix('⁻') {
+                    Some(after) => (-1, after),
+                    None => (1, after),
+                };
+                let end = after
+                    .find(|c| superscript_digit(c).is_none())
+                    .unwrap_or(after.len());
+                let digits: String = after[..end].chars().filter_map(superscript_digit).collect();
+                Some((sign * digits.parse::<i32>().ok()?, &after[end..]))
+            }
+        }
+    };
+    match power() {
+        Some((power, rest)) => (value * 10f64.powi(power), rest),
+        None => (value, input),
+    }
+}
+
+/// Splits leading digits in `radix` off `input`, dropping underscores between them
+/// (`1_000_000`) and, if `grouped`, commas between groups of three (`1,500,000`).
+fn digits(input: &str, radix: u32, grouped: bool) -> (String, &str) {
+    let mut digits = String::new();
+    let mut rest = input;
+    // Digits since the last comma; the first group can be short, the rest have three
+    let mut group = 0;
+    loop {
+        let mut chars = rest.chars();
+        match chars.next() {
+            Some(c) if c.is_digit(radix) => {
+                digits.push(c);
+                group += 1;
+                rest = chars.as_str();
+            }
+            Some('_') if !digits.is_empty() && chars.next().is_some_and(|c| c.is_digit(radix)) => {
+                rest = &rest[1..];
+            }
+            Some(',') if grouped && group > 0 && group <= 3 && is_thousands(&rest[1..]) => {
+                group = 0;
+                rest = &rest[1..];
+            }
+            _ => return (digits, rest),
+        }
+    }
+}
+
+/// Whether `input` starts with exactly three digits, as a group after a thousands comma.
+fn is_thousands(input: &str) -> bool {
+    let bytes = input.as_bytes();
+    bytes.len() >= 3
+        && bytes[..3].iter().all(u8::is_ascii_digit)
+        && !bytes.get(3).is_some_and(u8::is_ascii_digit)
+}
@@ -0,0 +1,324 @@
+use super::ConversionError;
+use super::lexer::{Token, TokenKind, tokenize};
+use super::registry::{self, Form, PrefixFamily, UnitMatch};
+use std::ops::Range;
+
+/// A parsed quantity or unit expression.
+#[derive(Debug)]
+pub(super) enum Expr {
+    Number(f64),
+    /// A unit and the byte range it was written at
+    Unit(UnitMatch, Range<usize>),
+    Mul(Box<Expr>, Box<Expr>),
+    Div(Box<Expr>, Box<Expr>),
+    Pow(Box<Expr>, i8),
+    /// Quantities written side by side to be added up, as in `5 ft 11 in`
+    Sum(Vec<Expr>),
+}
+
+impl Expr {
+    pub fn has_number(&self) -> bool {
+        match self {
+            Expr::Number(_) => true,
+            Expr::Unit(..) => false,
+            Expr::Mul(lhs, rhs) | Expr::Div(lhs, rhs) => lhs.has_number() || rhs.has_number(),
+            Expr::Pow(base, _) => base.has_number(),
+            Expr::Sum(terms) => terms.iter().any(Expr::has_number),
+        }
+    }
+
+    pub fn has_unit(&self) -> bool {
+        match self {
+            Expr::Number(_) => false,
+            Expr::Unit(..) => true,
+            Expr::Mul(lhs, rhs) | Expr::Div(lhs, rhs) => lhs.has_unit() || rhs.has_unit(),
+            Expr::Pow(base, _) => base.has_unit(),
+            Expr::Sum(terms) => terms.iter().any(Expr::has_unit),
+        }
+    }
+
+    /// Whether any unit carries a binary prefix, as in `GiB`.
+    pub fn has_binary_prefix(&self) -> bool {
+        match self {
+            Expr::Number(_) => false,
+            Expr::Unit(unit, _) => unit
+                .prefix
+                .is_some_and(|prefix| prefix.family == PrefixFamily::Binary),
+            Expr::Mul(lhs, rhs) | Expr::Div(lhs, rhs) => {
+                lhs.has_binary_prefix() || rhs.has_binary_prefix()
+            }
+            Expr::Pow(base, _) => base.has_binary_prefix(),
+            Expr::Sum(terms) => terms.iter().any(Expr::has_binary_prefix),
+        }
+    }
+
+    /// Where each unit whose case matters sits in the input, in order: symbols, and
+    /// names like "BTU" that aren't written in lowercase.
+    pub fn case_sensitive_spans(&self) -> Vec<Range<usize>> {
+        match self {
+            Expr::Number(_) => Vec::new(),
+            Expr::Unit(unit, span)
+                if unit.form == Form::Symbol || unit.def.name.chars().any(char::is_uppercase) =>
+            {
+                vec![span.clone()]
+            }
+            Expr::Unit(..) => Vec::new(),
+            Expr::Mul(lhs, rhs) | Expr::Div(lhs, rhs) => {
+                let mut spans = lhs.case_sensitive_spans();
+                spans.extend(rhs.case_sensitive_spans());
+                spans
+            }
+            Expr::Pow(base, _) => base.case_sensitive_spans(),
+            Expr::Sum(terms) => terms.iter().flat_map(Expr::case_sensitive_spans).collect(),
+        }
+    }
+}
+
+/// Words written before a unit that raise it to a power, as in `square meters`.
+fn leading_power(word: &str) -> Option<i8> {
+    match word.to_lowercase().as_str() {
+        "square" | "sq" => Some(2),
+        "cubic" | "cu" => Some(3),
+        _ => None,
+    }
+}
+
+/// Words written after a unit that raise it to a power, as in `meters squared`.
+fn trailing_power(word: &str) -> Option<i8> {
+    match word.to_lowercase().as_str() {
+        "squared" => Some(2),
+        "cubed" => Some(3),
+        _ => None,
+    }
+}
+
+fn unknown_unit(word: &str) -> ConversionError {
+    let candidates = registry::symbols_ignoring_case(word);
+    if candidates.len() > 1 {
+        return ConversionError::AmbiguousUnit {
+            unit: word.to_string(),
+            candidates: candidates.into_iter().map(|(_, symbol)| symbol).collect(),
+        };
+    }
+    ConversionError::UnknownUnit(word.to_string())
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token<'a>>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<TokenKind<'a>> {
+        self.tokens.get(self.pos).map(|token| token.kind)
+    }
+
+    fn next(&mut self) -> Option<TokenKind<'a>> {
+        let kind = self.peek();
+        self.pos += 1;
+        kind
+    }
+
+    /// Byte offset of the current token, or the end of the input once we run out.
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map_or(self.input.len(), |token| token.position)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ConversionError {
+        ConversionError::ParseError {
+            position: self.position(),
+            message: message.into(),
+        }
+    }
+
+    // expression := sum (('*' | '/' | 'per') sum)*
+    fn expression(&mut self) -> Result<Expr, ConversionError> {
+        let mut expr = self.sum()?;
+        loop {
+            match self.peek() {
+                Some(TokenKind::Star) => {
+                    self.next();
+                    expr = Expr::Mul(Box::new(expr), Box::new(self.sum()?));
+                }
+                Some(TokenKind::Slash) => {
+                    self.next();
+                    expr = Expr::Div(Box::new(expr), Box::new(self.sum()?));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    // sum := product (NUMBER product)*
+    //
+    // A number after a quantity starts another quantity to add to it, as in `5 ft 11 in`
+    // or `1 h 30 min`. Each term needs a unit of its own.
+    fn sum(&mut self) -> Result<Expr, ConversionError> {
+        let mut terms = vec![self.product()?];
+        while let Some(TokenKind::Number(_)) = self.peek() {
+            let term = self.product()?;
+            if !term.has_unit() {
+                return Err(self.error("expected a unit"));
+            }
+            terms.push(term);
+        }
+        Ok(match terms.len() {
+            1 => terms.remove(0),
+            _ => Expr::Sum(terms),
+        })
+    }
+
+    // product := power power*
+    //
+    // Writing two things side by side multiplies them, and binds tighter than an explicit
+    // operator: `liters / 100 kilometers` divides by a hundred kilometers. Two bare
+    // numbers side by side ("1 2 meters") are rejected rather than guessed at, and a
+    // number after a whole quantity starts the next term of a sum. A number straight
+    // after a bare unit, as in `$100`, still multiplies.
+    fn product(&mut self) -> Result<Expr, ConversionError> {
+        let mut expr = self.power()?;
+        while let Some(kind) = self.peek() {
+            match kind {
+                TokenKind::Number(_) if matches!(expr, Expr::Number(_)) => {
+                    return Err(self.error("expected a unit or operator"));
+                }
+                TokenKind::Number(_) if expr.has_number() && expr.has_unit() => break,
+                TokenKind::Number(_) | TokenKind::Word(_) | TokenKind::Open => {
+                    expr = Expr::Mul(Box::new(expr), Box::new(self.power()?));
+                }
+                _ => break,
+            }
+        }
+        Ok(expr)
+    }
+
+    // power := unary ('^' '-'? NUMBER | SUPERSCRIPT | 'squared' | 'cubed')?
+    fn power(&mut self) -> Result<Expr, ConversionError> {
+        let base = self.unary()?;
+        let exponent = match self.peek() {
+            Some(TokenKind::Caret) => {
+                self.next();
+                let sign = if self.peek() == Some(TokenKind::Minus) {
+                    self.next();
+                    -1
+                } else {
+                    1
+                };
+                // Exponents are capped at what a dimension holds. Powers of powers and
+                // products can still go past it, which evaluation reports as an error.
+                match self.peek() {
+                    Some(TokenKind::Number(n)) if n.fract() == 0.0 && n <= f64::from(i8::MAX) => {
+                        self.next();
+                        sign * n as i8
+                    }
+                    _ => return Err(self.error("expected a whole-number exponent")),
+                }
+            }
+            Some(TokenKind::Superscript(power)) => {
+                self.next();
+                power
+            }
+            Some(TokenKind::Word(word)) if trailing_power(word).is_some() => {
+                self.next();
+                trailing_power(word).unwrap_or(1)
+            }
+            _ => return Ok(base),
+        };
+        Ok(Expr::Pow(Box::new(base), exponent))
+    }
+
+    // unary := '-' unary | primary
+    fn unary(&mut self) -> Result<Expr, ConversionError> {
+        if self.peek() != Some(TokenKind::Minus) {
+            return self.primary();
+        }
+        self.next();
+        Ok(match self.unary()? {
+            Expr::Number(n) => Expr::Number(-n),
+            expr => Expr::Mul(Box::new(Expr::Number(-1.0)), Box::new(expr)),
+        })
+    }
+
+    // primary := NUMBER | '(' expression ')' | unit
+    fn primary(&mut self) -> Result<Expr, ConversionError> {
+        match self.peek() {
+            Some(TokenKind::Number(n)) => {
+                self.next();
+                Ok(Expr::Number(n))
+            }
+            Some(TokenKind::Open) => {
+                self.next();
+                let expr = self.expression()?;
+                if self.peek() != Some(TokenKind::Close) {
+                    return Err(self.error("expected ')'"));
+                }
+                self.next();
+                Ok(expr)
+            }
+            Some(TokenKind::Word(_)) => self.unit(),
+            Some(_) => Err(self.error("expected a number or unit")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    // unit := ('square' | 'cubic')? WORD+
+    //
+    // Registry names can span several words ("pound force"), so the longest run of words
+    // that names a unit wins. Any words after it are left for the next unit.
+    fn unit(&mut self) -> Result<Expr, ConversionError> {
+        let power = match self.peek() {
+            Some(TokenKind::Word(word)) => leading_power(word),
+            _ => None,
+        };
+        if power.is_some() {
+            self.next();
+        }
+
+        let mut words = Vec::new();
+        while let Some(TokenKind::Word(word)) =
+            self.tokens.get(self.pos + words.len()).map(|t| t.kind)
+        {
+            if leading_power(word).is_some() || trailing_power(word).is_some() {
+                break;
+            }
+            words.push(word);
+        }
+
+        let Some(&first) = words.first() else {
+            return Err(self.error("expected a unit"));
+        };
+        let (len, unit) = (1..=words.len())
+            .rev()
+            .find_map(|len| registry::lookup(&words[..len].join(" ")).map(|unit| (len, unit)))
+            .ok_or_else(|| unknown_unit(first))?;
+        let start = self.position();
+        self.pos += len;
+        let end = self.tokens[self.pos - 1].position + words[len - 1].len();
+        let unit = Expr::Unit(unit, start..end);
+
+        Ok(match power {
+            Some(power) => Expr::Pow(Box::new(unit), power),
+            None => unit,
+        })
+    }
+}
+
+/// Parses a quantity expression such as `(10 m)*(5 m)`, `60 miles / (1 hour)` or a bare
+/// unit expression like `kilograms / cubic meter` into an [`Expr`].
+pub(super) fn parse(input: &str) -> Result<Expr, ConversionError> {
+    let mut parser = Parser {
+        input,
+        tokens: tokenize(input)?,
+        pos: 0,
+    };
+
+    let expr = parser.expression()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(TokenKind::Close) => Err(parser.error("unmatched ')'")),
+        Some(_) => Err(parser.error("expected an operator")),
+    }
+}
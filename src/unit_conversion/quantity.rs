@@ -0,0 +1,160 @@
+use super::dimension::Dimension;
+use super::parser::Expr;
+use super::registry::{UnitDef, UnitMatch};
+use super::{ConversionError, ConversionOptions};
+
+/// The value of an evaluated expression, in coherent SI units of its dimension.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Quantity {
+    pub value: f64,
+    pub dimension: Dimension,
+    /// Set when the expression is a reading on an offset scale like celsius, or a level
+    /// on a logarithmic one like dBm. `value` is then the reading itself, since it isn't
+    /// a plain multiple of the SI unit.
+    pub absolute: Option<&'static UnitDef>,
+    /// Set when the expression was written as a temperature difference, like
+    /// `10 delta celsius`
+    pub interval: bool,
+}
+
+impl Quantity {
+    fn scalar(value: f64) -> Self {
+        Self::coherent(value, Dimension::NONE)
+    }
+
+    /// A value in the coherent SI unit of `dimension`, like seconds for time.
+    pub fn coherent(value: f64, dimension: Dimension) -> Self {
+        Self {
+            value,
+            dimension,
+            absolute: None,
+            interval: false,
+        }
+    }
+
+    fn unit(unit: &UnitMatch, options: &ConversionOptions) -> Result<Self, ConversionError> {
+        if unit.def.is_reading() && !unit.interval {
+            return Ok(Self::reading(1.0, unit.def));
+        }
+        Ok(Self {
+            value: unit.factor(options)?,
+            dimension: unit.def.dimension,
+            absolute: None,
+            interval: unit.interval,
+        })
+    }
+
+    fn reading(value: f64, def: &'static UnitDef) -> Self {
+        Self {
+            value,
+            dimension: def.dimension,
+            absolute: Some(def),
+            interval: false,
+        }
+    }
+
+    /// The value in SI units, resolving any offset scale.
+    pub fn base_value(&self, options: &ConversionOptions) -> f64 {
+        match self.absolute {
+            Some(def) => def.unit_to_base(self.value, options),
+            None => self.value,
+        }
+    }
+
+    /// A temperature inside a compound like `J/(kg*K)` or `meters / celsius` is always a
+    /// difference, so readings on offset scales drop their zero point here and convert
+    /// by scale factor alone. Logarithmic levels have no such fallback: scaling 30 dBm
+    /// as if it were 30 of something would be wrong, so they're refused instead.
+    fn relative(self) -> Result<Self, ConversionError> {
+        match self.absolute {
+            Some(def) if def.is_logarithmic() => Err(ConversionError::LogarithmicUnit(
+                def.symbols.first().unwrap_or(&def.name).to_string(),
+            )),
+            Some(def) => Ok(Self {
+                value: self.value * def.factor(),
+                absolute: None,
+                interval: true,
+                ..self
+            }),
+            None => Ok(self),
+        }
+    }
+
+    fn times(self, other: Self) -> Result<Self, ConversionError> {
+        let (lhs, rhs) = (self.relative()?, other.relative()?);
+        Ok(Self {
+            value: lhs.value * rhs.value,
+            dimension: lhs.dimension.times(rhs.dimension)?,
+            absolute: None,
+            interval: lhs.interval || rhs.interval,
+        })
+    }
+
+    fn per(self, other: Self) -> Result<Self, ConversionError> {
+        self.times(other.powi(-1)?)
+    }
+
+    /// Adds up the terms of a sum like `5 ft 11 in`, which all have to measure the same
+    /// thing. A leading minus sign covers the whole sum, so `-1 h 30 min` is minus an
+    /// hour and a half.
+    fn sum(terms: Vec<Self>) -> Result<Self, ConversionError> {
+        let mut terms = terms.into_iter().map(Self::relative);
+        let first = terms.next().unwrap_or(Ok(Self::scalar(0.0)))?;
+        let sign = if first.value < 0.0 { -1.0 } else { 1.0 };
+        terms.try_fold(first, |total, term| {
+            let term = term?;
+            if term.dimension != total.dimension {
+                return Err(ConversionError::IncompatibleTerms {
+                    first: super::describe_dimension(total.dimension),
+                    other: super::describe_dimension(term.dimension),
+                });
+            }
+            Ok(Self {
+                value: total.value + sign * term.value,
+                interval: total.interval || term.interval,
+                ..total
+            })
+        })
+    }
+
+    fn powi(self, power: i8) -> Result<Self, ConversionError> {
+        if power == 1 {
+            return Ok(self);
+        }
+        let base = self.relative()?;
+        Ok(Self {
+            value: base.value.powi(power.into()),
+            dimension: base.dimension.powi(power)?,
+            absolute: None,
+            interval: base.interval,
+        })
+    }
+}
+
+/// Evaluates an expression. `options` decide the size of units like the cup that vary
+/// by dialect, and of currencies.
+pub(super) fn evaluate(
+    expr: &Expr,
+    options: &ConversionOptions,
+) -> Result<Quantity, ConversionError> {
+    match expr {
+        Expr::Number(n) => Ok(Quantity::scalar(*n)),
+        Expr::Unit(unit, _) => Quantity::unit(unit, options),
+        // A number written straight before an offset or logarithmic scale is a reading on
+        // that scale
+        Expr::Mul(lhs, rhs) => match (lhs.as_ref(), rhs.as_ref()) {
+            (Expr::Number(n), Expr::Unit(unit, _)) if unit.def.is_reading() && !unit.interval => {
+                Ok(Quantity::reading(*n, unit.def))
+            }
+            _ => evaluate(lhs, options)?.times(evaluate(rhs, options)?),
+        },
+        Expr::Div(lhs, rhs) => evaluate(lhs, options)?.per(evaluate(rhs, options)?),
+        Expr::Pow(base, power) => evaluate(base, options)?.powi(*power),
+        Expr::Sum(terms) => Quantity::sum(
+            terms
+                .iter()
+                .map(|term| evaluate(term, options))
+                .collect::<Result<_, _>>()?,
+        ),
+    }
+}
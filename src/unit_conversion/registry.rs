@@ -0,0 +1,1546 @@
+use super::auto::System;
+use super::currency;
+use super::dimension::Dimension;
+use super::{ConversionError, ConversionOptions, VolumeDialect};
+
+/// How a unit's value maps onto the coherent SI unit of its dimension.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Scale {
+    /// `base = value * factor`
+    Linear(f64),
+    /// `base = value * factor + offset`, used for temperature scales with a shifted zero
+    Affine { factor: f64, offset: f64 },
+    /// `base = value * factor + ambient pressure`, for gauge pressures like psig that read
+    /// zero at whatever the surrounding atmosphere is
+    Gauge(f64),
+    /// `base = value * factor`, where the factor depends on the volume dialect in
+    /// `ConversionOptions`, for kitchen measures like the cup and the pint
+    Dialect(DialectFactors),
+    /// A currency, by ISO 4217 code, worth whatever the exchange rates in
+    /// `ConversionOptions` say
+    Exchange(&'static str),
+    /// `base = value * pixels * inch / dpi`, for pixels at the DPI in `ConversionOptions`
+    Pixel(f64),
+    /// `base = value * ems * font size`, for ems at the base font size in
+    /// `ConversionOptions`
+    FontSize(f64),
+    /// `base = reference * 10^(value / decade)`, for levels like decibels, where
+    /// `decade` is how many of the unit make a tenfold change in the base quantity
+    Log { reference: f64, decade: f64 },
+}
+
+/// The size of a kitchen measure in each [`VolumeDialect`], in cubic meters.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) struct DialectFactors {
+    pub us_customary: f64,
+    pub us_legal: f64,
+    pub imperial: f64,
+    pub metric: f64,
+}
+
+impl DialectFactors {
+    fn get(self, dialect: VolumeDialect) -> f64 {
+        match dialect {
+            VolumeDialect::UsCustomary => self.us_customary,
+            VolumeDialect::UsLegal => self.us_legal,
+            VolumeDialect::Imperial => self.imperial,
+            VolumeDialect::Metric => self.metric,
+        }
+    }
+}
+
+/// Which prefixes a unit accepts. Customary units like feet or gallons, and units with
+/// an offset zero like celsius, take none.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Prefixes {
+    None,
+    Si,
+    /// Bits and bytes take the IEC binary prefixes and the SI multiples from kilo up;
+    /// a millibyte means nothing, and `mb` is far more likely a mistyped megabit.
+    Data,
+}
+
+impl Prefixes {
+    pub fn accepts(self, prefix: &Prefix) -> bool {
+        match self {
+            Prefixes::None => false,
+            Prefixes::Si => prefix.family == PrefixFamily::Si,
+            Prefixes::Data => prefix.family == PrefixFamily::Binary || prefix.factor >= 1e3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum PrefixFamily {
+    /// Powers of ten: kilo, mega, ...
+    Si,
+    /// Powers of 1024: kibi, mebi, ...
+    Binary,
+}
+
+#[derive(Debug)]
+pub(super) struct Prefix {
+    pub names: &'static [&'static str],
+    pub symbols: &'static [&'static str],
+    pub factor: f64,
+    pub family: PrefixFamily,
+}
+
+pub(super) static SI_PREFIXES: &[Prefix] = &[
+    prefix(&["quetta"], &["Q"], 1e30),
+    prefix(&["ronna"], &["R"], 1e27),
+    prefix(&["yotta"], &["Y"], 1e24),
+    prefix(&["zetta"], &["Z"], 1e21),
+    prefix(&["exa"], &["E"], 1e18),
+    prefix(&["peta"], &["P"], 1e15),
+    prefix(&["tera"], &["T"], 1e12),
+    prefix(&["giga"], &["G"], 1e9),
+    prefix(&["mega"], &["M"], 1e6),
+    prefix(&["kilo"], &["k"], 1e3),
+    prefix(&["hecto"], &["h"], 1e2),
+    // "da" has to be tried before "d" so "dam" is a decameter, not a deci-"am"
+    prefix(&["deca", "deka"], &["da"], 1e1),
+    prefix(&["deci"], &["d"], 1e-1),
+    prefix(&["centi"], &["c"], 1e-2),
+    prefix(&["milli"], &["m"], 1e-3),
+    // The micro sign, the Greek mu, and "u" for keyboards without either
+    prefix(&["micro"], &["µ", "μ", "u"], 1e-6),
+    prefix(&["nano"], &["n"], 1e-9),
+    prefix(&["pico"], &["p"], 1e-12),
+    prefix(&["femto"], &["f"], 1e-15),
+    prefix(&["atto"], &["a"], 1e-18),
+    prefix(&["zepto"], &["z"], 1e-21),
+    prefix(&["yocto"], &["y"], 1e-24),
+    prefix(&["ronto"], &["r"], 1e-27),
+    prefix(&["quecto"], &["q"], 1e-30),
+];
+
+pub(super) static BINARY_PREFIXES: &[Prefix] = &[
+    binary_prefix(&["yobi"], &["Yi"], 8),
+    binary_prefix(&["zebi"], &["Zi"], 7),
+    binary_prefix(&["exbi"], &["Ei"], 6),
+    binary_prefix(&["pebi"], &["Pi"], 5),
+    binary_prefix(&["tebi"], &["Ti"], 4),
+    binary_prefix(&["gibi"], &["Gi"], 3),
+    binary_prefix(&["mebi"], &["Mi"], 2),
+    binary_prefix(&["kibi"], &["Ki"], 1),
+];
+
+const fn prefix(
+    names: &'static [&'static str],
+    symbols: &'static [&'static str],
+    factor: f64,
+) -> Prefix {
+    Prefix {
+        names,
+        symbols,
+        factor,
+        family: PrefixFamily::Si,
+    }
+}
+
+const fn binary_prefix(
+    names: &'static [&'static str],
+    symbols: &'static [&'static str],
+    power: u32,
+) -> Prefix {
+    let mut factor = 1.0;
+    let mut i = 0;
+    while i < power {
+        factor *= 1024.0;
+        i += 1;
+    }
+    Prefix {
+        names,
+        symbols,
+        factor,
+        family: PrefixFamily::Binary,
+    }
+}
+
+fn all_prefixes() -> impl Iterator<Item = &'static Prefix> {
+    SI_PREFIXES.iter().chain(BINARY_PREFIXES)
+}
+
+/// The largest prefix from `family` that `prefixes` allows and that keeps `value` at or
+/// above one, for picking a readable unit. `None` when the value is too small for any.
+pub(super) fn largest_prefix(
+    prefixes: Prefixes,
+    family: PrefixFamily,
+    value: f64,
+) -> Option<&'static Prefix> {
+    all_prefixes()
+        .filter(|prefix| prefix.family == family && prefixes.accepts(prefix))
+        .filter(|prefix| value.abs() >= prefix.factor)
+        .max_by(|a, b| a.factor.total_cmp(&b.factor))
+}
+
+#[derive(Debug)]
+pub(super) struct UnitDef {
+    pub name: &'static str,
+    pub plural: &'static str,
+    /// Other spellings, matched case-insensitively like the name
+    pub aliases: &'static [&'static str],
+    /// Abbreviations, matched exactly since case matters (`mm` vs `Mm`)
+    pub symbols: &'static [&'static str],
+    pub dimension: Dimension,
+    pub scale: Scale,
+    pub prefixes: Prefixes,
+    /// Offered as an `auto` output in a system, under each of these spellings
+    pub display: Option<(System, &'static [&'static str])>,
+}
+
+impl UnitDef {
+    const fn aliases(self, aliases: &'static [&'static str]) -> Self {
+        Self { aliases, ..self }
+    }
+
+    const fn symbols(self, symbols: &'static [&'static str]) -> Self {
+        Self { symbols, ..self }
+    }
+
+    /// Offers the unit as an `auto` output in `system`, written as each of `spellings`,
+    /// prefixed ones included, as in `&["mm", "m", "km"]`.
+    const fn shown(self, system: System, spellings: &'static [&'static str]) -> Self {
+        Self {
+            display: Some((system, spellings)),
+            ..self
+        }
+    }
+
+    const fn si_prefixes(self) -> Self {
+        Self {
+            prefixes: Prefixes::Si,
+            ..self
+        }
+    }
+
+    const fn gauge(self) -> Self {
+        Self {
+            scale: Scale::Gauge(self.factor()),
+            ..self
+        }
+    }
+
+    /// Makes the factor a number of pixels, sized by the DPI.
+    const fn dpi_relative(self) -> Self {
+        Self {
+            scale: Scale::Pixel(self.factor()),
+            ..self
+        }
+    }
+
+    /// Makes the factor a number of ems, sized by the base font size.
+    const fn font_relative(self) -> Self {
+        Self {
+            scale: Scale::FontSize(self.factor()),
+            ..self
+        }
+    }
+
+    const fn data_prefixes(self) -> Self {
+        Self {
+            prefixes: Prefixes::Data,
+            ..self
+        }
+    }
+
+    /// The size of one unit in SI terms, ignoring any zero offset. Kitchen measures give
+    /// their US customary size here, and units with no fixed size, like currencies,
+    /// pixels and decibels, give NaN; see [`UnitDef::size`].
+    pub const fn factor(&self) -> f64 {
+        match self.scale {
+            Scale::Linear(factor) | Scale::Affine { factor, .. } | Scale::Gauge(factor) => factor,
+            Scale::Dialect(factors) => factors.us_customary,
+            Scale::Exchange(_) | Scale::Pixel(_) | Scale::FontSize(_) | Scale::Log { .. } => {
+                f64::NAN
+            }
+        }
+    }
+
+    /// The size of one unit in SI terms under `options`, which only differs from
+    /// [`UnitDef::factor`] for kitchen measures like the cup, currencies, and the
+    /// typographic units that depend on a DPI or font size.
+    pub fn size(&self, options: &ConversionOptions) -> Result<f64, ConversionError> {
+        match self.scale {
+            Scale::Dialect(factors) => Ok(factors.get(options.volume_dialect)),
+            Scale::Exchange(code) => currency::value_of(code, options),
+            Scale::Pixel(pixels) => Ok(pixels * INCH / options.dpi),
+            Scale::FontSize(ems) => options
+                .font_size
+                .map(|size| ems * size)
+                .ok_or(ConversionError::MissingFontSize),
+            _ => Ok(self.factor()),
+        }
+    }
+
+    /// Whether a value on the scale is a reading rather than an amount: either its zero
+    /// is shifted away from the SI zero, or it isn't linear at all.
+    pub fn is_reading(&self) -> bool {
+        matches!(
+            self.scale,
+            Scale::Affine { .. } | Scale::Gauge(_) | Scale::Log { .. }
+        )
+    }
+
+    /// Whether the scale is logarithmic, so a value on it can't be scaled, multiplied or
+    /// divided like an amount.
+    pub fn is_logarithmic(&self) -> bool {
+        matches!(self.scale, Scale::Log { .. })
+    }
+
+    fn offset(&self, options: &ConversionOptions) -> f64 {
+        match self.scale {
+            Scale::Linear(_)
+            | Scale::Dialect(_)
+            | Scale::Exchange(_)
+            | Scale::Pixel(_)
+            | Scale::FontSize(_)
+            | Scale::Log { .. } => 0.0,
+            Scale::Affine { offset, .. } => offset,
+            Scale::Gauge(_) => options.ambient_pressure,
+        }
+    }
+
+    pub fn unit_to_base(&self, value: f64, options: &ConversionOptions) -> f64 {
+        match self.scale {
+            Scale::Log { reference, decade } => reference * 10f64.powf(value / decade),
+            _ => value * self.factor() + self.offset(options),
+        }
+    }
+
+    /// Errors for a logarithmic unit if `value` isn't positive, as it has no level.
+    pub fn base_to_unit(
+        &self,
+        value: f64,
+        options: &ConversionOptions,
+    ) -> Result<f64, ConversionError> {
+        match self.scale {
+            Scale::Log { .. } if value <= 0.0 => Err(ConversionError::NonPositiveLevel(
+                self.symbols.first().unwrap_or(&self.name).to_string(),
+            )),
+            Scale::Log { reference, decade } => Ok(decade * (value / reference).log10()),
+            _ => Ok((value - self.offset(options)) / self.factor()),
+        }
+    }
+
+    /// Matches a lowercased word against the name, plural and aliases. Those are
+    /// lowercase apart from acronyms like "BTU", which are compared ignoring case.
+    fn spelling(&self, word: &str) -> Option<Form> {
+        let is = |spelling: &str| spelling.eq_ignore_ascii_case(word);
+        if is(self.name) {
+            Some(Form::Singular)
+        } else if is(self.plural) {
+            Some(Form::Plural)
+        } else if self.aliases.iter().any(|alias| is(alias)) {
+            Some(Form::Alias)
+        } else {
+            None
+        }
+    }
+}
+
+/// How a unit was written, which decides how it's echoed back in the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Form {
+    Singular,
+    Plural,
+    Alias,
+    Symbol,
+}
+
+/// A registry unit as written in an expression, with any prefix that was attached to it.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct UnitMatch {
+    pub def: &'static UnitDef,
+    pub prefix: Option<&'static Prefix>,
+    pub form: Form,
+    /// Written as a temperature difference (`delta celsius`, `Δ°F`), which converts by
+    /// scale factor alone and ignores the zero point
+    pub interval: bool,
+}
+
+impl UnitMatch {
+    pub fn factor(&self, options: &ConversionOptions) -> Result<f64, ConversionError> {
+        Ok(self.def.size(options)? * self.prefix.map_or(1.0, |prefix| prefix.factor))
+    }
+
+    /// The singular or plural long name, e.g. "kilometers". Aliases and symbols are only
+    /// ever echoed back as written, so this is `None` for those.
+    pub fn name(&self, plural: bool) -> Option<String> {
+        if self.interval || !matches!(self.form, Form::Singular | Form::Plural) {
+            return None;
+        }
+        let name = if plural {
+            self.def.plural
+        } else {
+            self.def.name
+        };
+        Some(match self.prefix {
+            Some(prefix) => format!("{}{name}", prefix.names[0]),
+            None => name.to_string(),
+        })
+    }
+}
+
+/// Finds the unit a word refers to. A temperature unit written as a difference, as in
+/// `delta celsius`, `Δ°F` or `kelvin difference`, is looked up like the plain unit and
+/// marked as an interval.
+pub(super) fn lookup(word: &str) -> Option<UnitMatch> {
+    match strip_interval(word) {
+        Some(unit) => lookup_unit(unit)
+            .filter(|unit| unit.def.dimension == Dimension::TEMPERATURE)
+            .map(|unit| UnitMatch {
+                interval: true,
+                ..unit
+            }),
+        None => lookup_unit(word),
+    }
+}
+
+fn strip_interval(word: &str) -> Option<&str> {
+    if let Some(rest) = word.strip_prefix(['Δ', '∆']) {
+        return Some(rest.trim_start());
+    }
+    if let Some((first, rest)) = word.split_once(' ')
+        && first.eq_ignore_ascii_case("delta")
+    {
+        return Some(rest);
+    }
+    word.rsplit_once(' ')
+        .filter(|(_, last)| last.eq_ignore_ascii_case("difference"))
+        .map(|(rest, _)| rest)
+}
+
+/// Finds the unit a word refers to, trying each of these in turn:
+///
+/// 1. an exact, case-sensitive symbol: `m`, `K`, `°C`
+/// 2. a name, plural or alias in any case: `meters`, `Feet`
+/// 3. a prefixed symbol, case-sensitive: `mm` is a millimeter, `Mm` a megameter
+/// 4. a prefixed name in any case: `Kilometers`
+/// 5. a symbol written in the wrong case, if only one unit could be meant: `KG`, `pa`;
+///    see [`symbols_ignoring_case`] for how clashes there are settled
+///
+/// Unprefixed matches come first so `ft` stays a foot rather than a femto-"t", and a
+/// symbol in its proper case always beats one that only matches ignoring case.
+fn lookup_unit(word: &str) -> Option<UnitMatch> {
+    let lower = word.to_lowercase();
+    let unprefixed = |form: Form, def: &'static UnitDef| UnitMatch {
+        def,
+        prefix: None,
+        form,
+        interval: false,
+    };
+
+    UNITS
+        .iter()
+        .find(|def| def.symbols.contains(&word))
+        .map(|def| {
+            // A symbol spelled like the name ("bit") still pluralizes like the name
+            let form = if word == def.name {
+                Form::Singular
+            } else {
+                Form::Symbol
+            };
+            unprefixed(form, def)
+        })
+        .or_else(|| {
+            UNITS
+                .iter()
+                .find_map(|def| def.spelling(&lower).map(|form| unprefixed(form, def)))
+        })
+        .or_else(|| lookup_prefixed(word, &lower))
+        .or_else(|| match symbols_ignoring_case(word).as_slice() {
+            [(unit, _)] => Some(*unit),
+            _ => None,
+        })
+}
+
+/// The units whose symbol matches `word` when case is ignored, along with the symbol as
+/// it should be written. Only the closest matches are kept, in this order:
+///
+/// 1. unprefixed symbols: `pa` is a pascal, never a picoampere
+/// 2. prefixed symbols where only the prefix is in the wrong case: `KB` is a kilobyte
+///    and `mb` a megabit
+/// 3. any other prefixed symbol
+///
+/// More than one match means the word is ambiguous, as `MM` is between Mm and mm.
+pub(super) fn symbols_ignoring_case(word: &str) -> Vec<(UnitMatch, String)> {
+    let lower = word.to_lowercase();
+    let mut found: Vec<(UnitMatch, String, u8)> = Vec::new();
+    let mut push = |def: &'static UnitDef, prefix: Option<&'static Prefix>, symbol: &str| {
+        let spelled = match prefix {
+            Some(prefix) => format!("{}{symbol}", prefix.symbols[0]),
+            None => symbol.to_string(),
+        };
+        let rank = match prefix {
+            None => 0,
+            Some(_) if word.len() == spelled.len() && word.ends_with(symbol) => 1,
+            Some(_) => 2,
+        };
+        // Units with several symbols, like "L" and "l", still only count once
+        let seen = found.iter().any(|(unit, _, _)| {
+            std::ptr::eq(unit.def, def)
+                && unit.prefix.map(std::ptr::from_ref) == prefix.map(std::ptr::from_ref)
+        });
+        if !seen && spelled.to_lowercase() == lower {
+            let form = Form::Symbol;
+            let unit = UnitMatch {
+                def,
+                prefix,
+                form,
+                interval: false,
+            };
+            found.push((unit, spelled, rank));
+        }
+    };
+
+    for def in UNITS {
+        for symbol in def.symbols {
+            push(def, None, symbol);
+            for prefix in all_prefixes().filter(|prefix| def.prefixes.accepts(prefix)) {
+                push(def, Some(prefix), symbol);
+            }
+        }
+    }
+
+    let closest = found.iter().map(|(_, _, rank)| *rank).min();
+    found
+        .into_iter()
+        .filter(|(_, _, rank)| Some(*rank) == closest)
+        .map(|(unit, spelled, _)| (unit, spelled))
+        .collect()
+}
+
+fn lookup_prefixed(word: &str, lower: &str) -> Option<UnitMatch> {
+    let prefixable =
+        |prefix: &'static Prefix| UNITS.iter().filter(|def| def.prefixes.accepts(prefix));
+
+    for prefix in all_prefixes() {
+        for symbol in prefix.symbols {
+            if let Some(rest) = word.strip_prefix(symbol)
+                && let Some(def) = prefixable(prefix).find(|def| def.symbols.contains(&rest))
+            {
+                return Some(UnitMatch {
+                    def,
+                    prefix: Some(prefix),
+                    form: Form::Symbol,
+                    interval: false,
+                });
+            }
+        }
+    }
+
+    for prefix in all_prefixes() {
+        for name in prefix.names {
+            if let Some(rest) = lower.strip_prefix(name)
+                && let Some((def, form)) =
+                    prefixable(prefix).find_map(|def| def.spelling(rest).map(|form| (def, form)))
+            {
+                return Some(UnitMatch {
+                    def,
+                    prefix: Some(prefix),
+                    form,
+                    interval: false,
+                });
+            }
+        }
+    }
+
+    None
+}
+
+const fn unit(
+    name: &'static str,
+    plural: &'static str,
+    dimension: Dimension,
+    factor: f64,
+) -> UnitDef {
+    UnitDef {
+        name,
+        plural,
+        aliases: &[],
+        symbols: &[],
+        dimension,
+        scale: Scale::Linear(factor),
+        prefixes: Prefixes::None,
+        display: None,
+    }
+}
+
+const fn kitchen(name: &'static str, plural: &'static str, factors: DialectFactors) -> UnitDef {
+    UnitDef {
+        scale: Scale::Dialect(factors),
+        ..unit(name, plural, Dimension::VOLUME, factors.us_customary)
+    }
+}
+
+const fn currency(name: &'static str, plural: &'static str, code: &'static str) -> UnitDef {
+    UnitDef {
+        scale: Scale::Exchange(code),
+        ..unit(name, plural, Dimension::CURRENCY, f64::NAN)
+    }
+}
+
+/// A level on a logarithmic scale, like the decibel. Power levels have a `decade` of
+/// 10 units; field quantities like voltage, whose power goes with their square, have 20.
+const fn level(
+    name: &'static str,
+    plural: &'static str,
+    dimension: Dimension,
+    reference: f64,
+    decade: f64,
+) -> UnitDef {
+    UnitDef {
+        scale: Scale::Log { reference, decade },
+        ..unit(name, plural, dimension, f64::NAN)
+    }
+}
+
+const fn affine(
+    name: &'static str,
+    plural: &'static str,
+    dimension: Dimension,
+    factor: f64,
+    offset: f64,
+) -> UnitDef {
+    UnitDef {
+        scale: Scale::Affine { factor, offset },
+        ..unit(name, plural, dimension, factor)
+    }
+}
+
+// Only named units live here; compound units like "kilograms / cubic meter" are built
+// from these by the expression parser, and prefixed forms like "kilometer" come from
+// `SI_PREFIXES`. Factors use the exact definitions where one exists (international foot
+// and pound, US liquid gallon).
+const FOOT: f64 = 0.3048;
+const INCH: f64 = 0.0254;
+const MILE: f64 = 1609.344;
+const YARD: f64 = 3.0 * FOOT;
+const CHAIN: f64 = 22.0 * YARD;
+const POINT: f64 = INCH / 72.0;
+const POUND: f64 = 0.453_592_37;
+const GRAIN: f64 = POUND / 7000.0;
+const TROY_OUNCE: f64 = 480.0 * GRAIN;
+const GALLON: f64 = 3.785_411_784e-3;
+const US_FLUID_OUNCE: f64 = GALLON / 128.0;
+const IMPERIAL_GALLON: f64 = 4.546_09e-3;
+const IMPERIAL_FLUID_OUNCE: f64 = IMPERIAL_GALLON / 160.0;
+const MINUTE: f64 = 60.0;
+const HOUR: f64 = 3600.0;
+const DAY: f64 = 24.0 * HOUR;
+const YEAR: f64 = 365.25 * DAY;
+const STANDARD_GRAVITY: f64 = 9.806_65;
+const NAUTICAL_MILE: f64 = 1852.0;
+const SPEED_OF_LIGHT: f64 = 299_792_458.0;
+const ASTRONOMICAL_UNIT: f64 = 149_597_870_700.0;
+const TURN: f64 = std::f64::consts::TAU;
+const CALORIE: f64 = 4.184;
+const IT_CALORIE: f64 = 4.1868;
+// The International Table BTU, defined through the IT calorie and the pound
+const BTU: f64 = 1_055.055_852_62;
+pub(super) const STANDARD_ATMOSPHERE: f64 = 101_325.0;
+const PSI: f64 = POUND * STANDARD_GRAVITY / (INCH * INCH);
+const BAR: f64 = 1e5;
+// Conventional millimeter of mercury, as used by manometers and blood pressure
+const MMHG: f64 = 133.322_387_415;
+
+pub(super) static UNITS: &[UnitDef] = &[
+    // Length
+    unit("meter", "meters", Dimension::LENGTH, 1.0)
+        .aliases(&["metre", "metres"])
+        .symbols(&["m"])
+        .si_prefixes()
+        .shown(System::Metric, &["nm", "µm", "mm", "cm", "m", "km"]),
+    // Feet and inches can also be written with quote marks, as in `6' 2"`, but the
+    // proper primes (′ ″) are arcminutes and arcseconds
+    unit("inch", "inches", Dimension::LENGTH, INCH)
+        .symbols(&["in", "\"", "”"])
+        .shown(System::Imperial, &["in"]),
+    unit("foot", "feet", Dimension::LENGTH, FOOT)
+        .symbols(&["ft", "'", "’"])
+        .shown(System::Imperial, &["ft"]),
+    unit("mile", "miles", Dimension::LENGTH, MILE)
+        .symbols(&["mi"])
+        .shown(System::Imperial, &["mi"]),
+    unit("yard", "yards", Dimension::LENGTH, YARD).symbols(&["yd"]),
+    unit("thou", "thou", Dimension::LENGTH, INCH / 1000.0).aliases(&["mil", "mils"]),
+    unit("angstrom", "angstroms", Dimension::LENGTH, 1e-10)
+        .aliases(&["ångström", "ångströms"])
+        // The letter Å and the dedicated angstrom sign
+        .symbols(&["Å", "Å"]),
+    unit("micron", "microns", Dimension::LENGTH, 1e-6),
+    // Gunter's chain, and the surveying units built on it
+    unit("chain", "chains", Dimension::LENGTH, CHAIN).symbols(&["ch"]),
+    unit("furlong", "furlongs", Dimension::LENGTH, 10.0 * CHAIN).symbols(&["fur"]),
+    unit("league", "leagues", Dimension::LENGTH, 3.0 * MILE).symbols(&["lea"]),
+    // Nautical
+    unit(
+        "nautical mile",
+        "nautical miles",
+        Dimension::LENGTH,
+        NAUTICAL_MILE,
+    )
+    .symbols(&["nmi", "NM"]),
+    // The international cable, a tenth of a nautical mile
+    unit("cable", "cables", Dimension::LENGTH, NAUTICAL_MILE / 10.0)
+        .aliases(&["cable length", "cable lengths"]),
+    unit("fathom", "fathoms", Dimension::LENGTH, 6.0 * FOOT).symbols(&["ftm"]),
+    // Astronomical. Light travel distances use the Julian year, like the IAU light-year,
+    // and the parsec is the distance at which one AU subtends one arcsecond.
+    unit(
+        "astronomical unit",
+        "astronomical units",
+        Dimension::LENGTH,
+        ASTRONOMICAL_UNIT,
+    )
+    .symbols(&["au", "AU"]),
+    unit(
+        "light-second",
+        "light-seconds",
+        Dimension::LENGTH,
+        SPEED_OF_LIGHT,
+    )
+    .aliases(&["light second", "light seconds"]),
+    unit(
+        "light-minute",
+        "light-minutes",
+        Dimension::LENGTH,
+        SPEED_OF_LIGHT * MINUTE,
+    )
+    .aliases(&["light minute", "light minutes"]),
+    unit(
+        "light-hour",
+        "light-hours",
+        Dimension::LENGTH,
+        SPEED_OF_LIGHT * HOUR,
+    )
+    .aliases(&["light hour", "light hours"]),
+    unit(
+        "light-day",
+        "light-days",
+        Dimension::LENGTH,
+        SPEED_OF_LIGHT * DAY,
+    )
+    .aliases(&["light day", "light days"]),
+    unit(
+        "light-year",
+        "light-years",
+        Dimension::LENGTH,
+        SPEED_OF_LIGHT * YEAR,
+    )
+    .aliases(&["light year", "light years", "lightyear", "lightyears"])
+    .symbols(&["ly"]),
+    unit(
+        "parsec",
+        "parsecs",
+        Dimension::LENGTH,
+        ASTRONOMICAL_UNIT * 648_000.0 / std::f64::consts::PI,
+    )
+    .symbols(&["pc"])
+    .si_prefixes(),
+    // Typographic. Points are PostScript (desktop publishing) points, as in CSS. Pixels
+    // depend on the DPI and ems on the base font size, both set in `ConversionOptions`.
+    // "pc" is the parsec, so the pica has no symbol.
+    unit("point", "points", Dimension::LENGTH, POINT).symbols(&["pt"]),
+    unit("pica", "picas", Dimension::LENGTH, 12.0 * POINT),
+    unit("twip", "twips", Dimension::LENGTH, POINT / 20.0),
+    unit("pixel", "pixels", Dimension::LENGTH, 1.0)
+        .symbols(&["px"])
+        .dpi_relative(),
+    // There's only the one base font size, so an em and a rem are the same
+    unit("em", "ems", Dimension::LENGTH, 1.0).font_relative(),
+    unit("rem", "rems", Dimension::LENGTH, 1.0).font_relative(),
+    // Mass
+    // The gram carries the prefixes, so the kilogram is just kilo + gram
+    unit("gram", "grams", Dimension::MASS, 1e-3)
+        .aliases(&["gramme", "grammes"])
+        .symbols(&["g"])
+        .si_prefixes()
+        .shown(System::Metric, &["µg", "mg", "g", "kg"]),
+    unit("pound", "pounds", Dimension::MASS, POUND)
+        .aliases(&["lbs"])
+        .symbols(&["lb"])
+        .shown(System::Imperial, &["lb"]),
+    unit("ounce", "ounces", Dimension::MASS, POUND / 16.0)
+        .symbols(&["oz"])
+        .shown(System::Imperial, &["oz"]),
+    unit("dram", "drams", Dimension::MASS, POUND / 256.0).symbols(&["dr"]),
+    unit("stone", "stones", Dimension::MASS, 14.0 * POUND).symbols(&["st"]),
+    unit("grain", "grains", Dimension::MASS, GRAIN).symbols(&["gr"]),
+    // The metric carat used for gemstones
+    unit("carat", "carats", Dimension::MASS, 0.2e-3).symbols(&["ct"]),
+    // A plain "ton" is the US short ton; prefixed tonnes (kt, Mt, Gt) are common enough
+    // in emissions and explosive yields to be worth accepting
+    unit("ton", "tons", Dimension::MASS, 2000.0 * POUND)
+        .aliases(&["short ton", "short tons", "us ton", "us tons"])
+        .shown(System::Imperial, &["tons"]),
+    unit("long ton", "long tons", Dimension::MASS, 2240.0 * POUND).aliases(&[
+        "imperial ton",
+        "imperial tons",
+        "uk ton",
+        "uk tons",
+    ]),
+    unit("tonne", "tonnes", Dimension::MASS, 1e3)
+        .aliases(&["metric ton", "metric tons"])
+        .symbols(&["t"])
+        .si_prefixes()
+        .shown(System::Metric, &["t"]),
+    // Troy weights, for precious metals. The grain is shared with avoirdupois.
+    unit("troy ounce", "troy ounces", Dimension::MASS, TROY_OUNCE).symbols(&["ozt", "oz t"]),
+    unit(
+        "troy pound",
+        "troy pounds",
+        Dimension::MASS,
+        12.0 * TROY_OUNCE,
+    )
+    .symbols(&["lb t"]),
+    unit("pennyweight", "pennyweights", Dimension::MASS, 24.0 * GRAIN).symbols(&["dwt"]),
+    // Apothecaries' weights share the troy ounce and pound
+    unit("scruple", "scruples", Dimension::MASS, 20.0 * GRAIN).symbols(&["℈"]),
+    unit(
+        "apothecary dram",
+        "apothecary drams",
+        Dimension::MASS,
+        60.0 * GRAIN,
+    )
+    .aliases(&["drachm", "drachms"])
+    .symbols(&["ʒ"]),
+    unit(
+        "apothecary ounce",
+        "apothecary ounces",
+        Dimension::MASS,
+        TROY_OUNCE,
+    )
+    .symbols(&["℥"]),
+    unit(
+        "apothecary pound",
+        "apothecary pounds",
+        Dimension::MASS,
+        12.0 * TROY_OUNCE,
+    ),
+    // Time
+    unit("second", "seconds", Dimension::TIME, 1.0)
+        .aliases(&["sec", "secs"])
+        .symbols(&["s"])
+        .si_prefixes()
+        .shown(System::Metric, &["ns", "µs", "ms", "s"]),
+    unit("minute", "minutes", Dimension::TIME, MINUTE)
+        .aliases(&["mins"])
+        .symbols(&["min"])
+        .shown(System::Metric, &["min"]),
+    unit("hour", "hours", Dimension::TIME, HOUR)
+        .aliases(&["hr", "hrs"])
+        .symbols(&["h"])
+        .shown(System::Metric, &["h"]),
+    unit("day", "days", Dimension::TIME, DAY)
+        .symbols(&["d"])
+        .shown(System::Metric, &["days"]),
+    unit("week", "weeks", Dimension::TIME, 7.0 * DAY).aliases(&["wk", "wks"]),
+    // Months and years are Julian averages, the convention for converting durations
+    unit("month", "months", Dimension::TIME, YEAR / 12.0).aliases(&["mo", "mos"]),
+    unit("year", "years", Dimension::TIME, YEAR)
+        .aliases(&["yr", "yrs"])
+        .shown(System::Metric, &["years"]),
+    unit("decade", "decades", Dimension::TIME, 10.0 * YEAR),
+    unit("century", "centuries", Dimension::TIME, 100.0 * YEAR),
+    unit("millennium", "millennia", Dimension::TIME, 1000.0 * YEAR),
+    // Temperature
+    // Bare "C" and "F" are left for the coulomb and farad
+    unit("kelvin", "kelvin", Dimension::TEMPERATURE, 1.0)
+        .symbols(&["K"])
+        .si_prefixes(),
+    unit("rankine", "rankine", Dimension::TEMPERATURE, 5.0 / 9.0)
+        .aliases(&["degree rankine", "degrees rankine", "degr"])
+        .symbols(&["°R", "°Ra"]),
+    affine("celsius", "celsius", Dimension::TEMPERATURE, 1.0, 273.15)
+        .aliases(&["degree celsius", "degrees celsius", "degc"])
+        .symbols(&["°C", "℃"])
+        .shown(System::Metric, &["°C"]),
+    affine(
+        "fahrenheit",
+        "fahrenheit",
+        Dimension::TEMPERATURE,
+        5.0 / 9.0,
+        459.67 * 5.0 / 9.0,
+    )
+    .aliases(&["degree fahrenheit", "degrees fahrenheit", "degf"])
+    .symbols(&["°F", "℉"])
+    .shown(System::Imperial, &["°F"]),
+    affine("réaumur", "réaumur", Dimension::TEMPERATURE, 1.25, 273.15)
+        .aliases(&["reaumur", "degree réaumur", "degrees réaumur"])
+        .symbols(&["°Ré", "°Re"]),
+    // Delisle counts down from the boiling point of water, so its degrees are negative
+    // kelvin and a rise in temperature is a fall in degrees Delisle
+    affine(
+        "delisle",
+        "delisle",
+        Dimension::TEMPERATURE,
+        -2.0 / 3.0,
+        373.15,
+    )
+    .aliases(&["degree delisle", "degrees delisle"])
+    .symbols(&["°De"]),
+    // Volume
+    unit("liter", "liters", Dimension::VOLUME, 1e-3)
+        .aliases(&["litre", "litres"])
+        .symbols(&["L", "l"])
+        .si_prefixes()
+        // Past a thousand liters, volumes read better in cubic meters
+        .shown(System::Metric, &["mL", "L", "m³"]),
+    // The cc of engines and syringes. Written out in an expression, "cubic centimeter" is
+    // read as the centimeter cubed, which comes to the same thing.
+    unit(
+        "cubic centimeter",
+        "cubic centimeters",
+        Dimension::VOLUME,
+        1e-6,
+    )
+    .symbols(&["cc"]),
+    // Kitchen measures. "pt" is the typographic point, so pints have no symbol. The plain
+    // names mean whatever the volume dialect in `ConversionOptions` says, US customary by
+    // default; the qualified names below them always mean the same thing. US legal sizes
+    // are the rounded metric ones US nutrition labels use. The metric dialect has a 250 mL
+    // cup and 15 mL tablespoon, and keeps imperial sizes for the larger measures, as
+    // Australia and New Zealand do.
+    kitchen(
+        "teaspoon",
+        "teaspoons",
+        DialectFactors {
+            us_customary: US_FLUID_OUNCE / 6.0,
+            us_legal: 5e-6,
+            imperial: IMPERIAL_FLUID_OUNCE * 5.0 / 24.0,
+            metric: 5e-6,
+        },
+    )
+    .aliases(&["tsps"])
+    .symbols(&["tsp"]),
+    kitchen(
+        "tablespoon",
+        "tablespoons",
+        DialectFactors {
+            us_customary: US_FLUID_OUNCE / 2.0,
+            us_legal: 15e-6,
+            imperial: IMPERIAL_FLUID_OUNCE * 5.0 / 8.0,
+            metric: 15e-6,
+        },
+    )
+    .aliases(&["tbsps"])
+    .symbols(&["tbsp", "Tbsp"]),
+    kitchen(
+        "fluid ounce",
+        "fluid ounces",
+        DialectFactors {
+            us_customary: US_FLUID_OUNCE,
+            us_legal: 30e-6,
+            imperial: IMPERIAL_FLUID_OUNCE,
+            metric: IMPERIAL_FLUID_OUNCE,
+        },
+    )
+    .symbols(&["fl oz", "floz"])
+    .shown(System::Imperial, &["fl oz"]),
+    kitchen(
+        "cup",
+        "cups",
+        DialectFactors {
+            us_customary: GALLON / 16.0,
+            us_legal: 240e-6,
+            imperial: IMPERIAL_GALLON / 16.0,
+            metric: 250e-6,
+        },
+    ),
+    kitchen(
+        "pint",
+        "pints",
+        DialectFactors {
+            us_customary: GALLON / 8.0,
+            us_legal: GALLON / 8.0,
+            imperial: IMPERIAL_GALLON / 8.0,
+            metric: IMPERIAL_GALLON / 8.0,
+        },
+    ),
+    kitchen(
+        "quart",
+        "quarts",
+        DialectFactors {
+            us_customary: GALLON / 4.0,
+            us_legal: GALLON / 4.0,
+            imperial: IMPERIAL_GALLON / 4.0,
+            metric: IMPERIAL_GALLON / 4.0,
+        },
+    )
+    .symbols(&["qt"]),
+    kitchen(
+        "gallon",
+        "gallons",
+        DialectFactors {
+            us_customary: GALLON,
+            us_legal: GALLON,
+            imperial: IMPERIAL_GALLON,
+            metric: IMPERIAL_GALLON,
+        },
+    )
+    .symbols(&["gal"])
+    .shown(System::Imperial, &["gal"]),
+    unit(
+        "US teaspoon",
+        "US teaspoons",
+        Dimension::VOLUME,
+        US_FLUID_OUNCE / 6.0,
+    )
+    .aliases(&["us tsp"]),
+    unit(
+        "US tablespoon",
+        "US tablespoons",
+        Dimension::VOLUME,
+        US_FLUID_OUNCE / 2.0,
+    )
+    .aliases(&["us tbsp"]),
+    unit(
+        "US fluid ounce",
+        "US fluid ounces",
+        Dimension::VOLUME,
+        US_FLUID_OUNCE,
+    )
+    .aliases(&["us fl oz"]),
+    unit("US cup", "US cups", Dimension::VOLUME, GALLON / 16.0),
+    unit("US pint", "US pints", Dimension::VOLUME, GALLON / 8.0).aliases(&["us pt"]),
+    unit("US quart", "US quarts", Dimension::VOLUME, GALLON / 4.0).aliases(&["us qt"]),
+    unit("US gallon", "US gallons", Dimension::VOLUME, GALLON).aliases(&["us gal"]),
+    unit(
+        "US legal teaspoon",
+        "US legal teaspoons",
+        Dimension::VOLUME,
+        5e-6,
+    ),
+    unit(
+        "US legal tablespoon",
+        "US legal tablespoons",
+        Dimension::VOLUME,
+        15e-6,
+    ),
+    unit(
+        "US legal fluid ounce",
+        "US legal fluid ounces",
+        Dimension::VOLUME,
+        30e-6,
+    )
+    .aliases(&["us legal fl oz"]),
+    unit("US legal cup", "US legal cups", Dimension::VOLUME, 240e-6),
+    unit(
+        "imperial teaspoon",
+        "imperial teaspoons",
+        Dimension::VOLUME,
+        IMPERIAL_FLUID_OUNCE * 5.0 / 24.0,
+    )
+    .aliases(&["imp tsp"]),
+    unit(
+        "imperial tablespoon",
+        "imperial tablespoons",
+        Dimension::VOLUME,
+        IMPERIAL_FLUID_OUNCE * 5.0 / 8.0,
+    )
+    .aliases(&["imp tbsp"]),
+    unit(
+        "imperial fluid ounce",
+        "imperial fluid ounces",
+        Dimension::VOLUME,
+        IMPERIAL_FLUID_OUNCE,
+    )
+    .aliases(&["imperial fl oz", "imp fl oz", "uk fl oz"]),
+    unit(
+        "imperial cup",
+        "imperial cups",
+        Dimension::VOLUME,
+        IMPERIAL_GALLON / 16.0,
+    ),
+    unit(
+        "imperial pint",
+        "imperial pints",
+        Dimension::VOLUME,
+        IMPERIAL_GALLON / 8.0,
+    )
+    .aliases(&["imp pt", "uk pint", "uk pints"]),
+    unit(
+        "imperial quart",
+        "imperial quarts",
+        Dimension::VOLUME,
+        IMPERIAL_GALLON / 4.0,
+    )
+    .aliases(&["imp qt"]),
+    unit(
+        "imperial gallon",
+        "imperial gallons",
+        Dimension::VOLUME,
+        IMPERIAL_GALLON,
+    )
+    .aliases(&["imp gal", "uk gallon", "uk gallons"]),
+    unit(
+        "metric teaspoon",
+        "metric teaspoons",
+        Dimension::VOLUME,
+        5e-6,
+    ),
+    // Australia kept a larger tablespoon than the rest of the metric world
+    unit(
+        "metric tablespoon",
+        "metric tablespoons",
+        Dimension::VOLUME,
+        15e-6,
+    ),
+    unit(
+        "australian tablespoon",
+        "australian tablespoons",
+        Dimension::VOLUME,
+        20e-6,
+    ),
+    unit("metric cup", "metric cups", Dimension::VOLUME, 250e-6),
+    // Velocity
+    unit("mph", "mph", Dimension::VELOCITY, MILE / HOUR).shown(System::Imperial, &["mph"]),
+    unit("kph", "kph", Dimension::VELOCITY, 1e3 / HOUR)
+        .aliases(&["kmh"])
+        .shown(System::Metric, &["km/h"]),
+    // "kt" is left for the kilotonne
+    unit("knot", "knots", Dimension::VELOCITY, NAUTICAL_MILE / HOUR).symbols(&["kn"]),
+    // Area
+    // US survey acre, matching the value uom used before the registry
+    unit("acre", "acres", Dimension::AREA, 4_046.873)
+        .symbols(&["ac"])
+        .shown(System::Imperial, &["acres"]),
+    // Force
+    unit("newton", "newtons", Dimension::FORCE, 1.0)
+        .symbols(&["N"])
+        .si_prefixes()
+        .shown(System::Metric, &["N", "kN", "MN"]),
+    // Torque, which has the dimension of energy
+    unit("newton meter", "newton meters", Dimension::ENERGY, 1.0)
+        .aliases(&["newton metre", "newton metres"])
+        .symbols(&["Nm"]),
+    unit(
+        "pound force",
+        "pounds force",
+        Dimension::FORCE,
+        POUND * STANDARD_GRAVITY,
+    )
+    .symbols(&["lbf"])
+    .shown(System::Imperial, &["lbf"]),
+    unit(
+        "kilogram force",
+        "kilograms force",
+        Dimension::FORCE,
+        STANDARD_GRAVITY,
+    )
+    .aliases(&["kilopond", "kiloponds"])
+    .symbols(&["kgf", "kp"]),
+    // Pressure
+    // Plain pressure units are absolute. Gauge units (psig, barg) read zero at the
+    // ambient pressure in `ConversionOptions`, one standard atmosphere by default.
+    unit("pascal", "pascals", Dimension::PRESSURE, 1.0)
+        .symbols(&["Pa"])
+        .si_prefixes()
+        .shown(System::Metric, &["Pa", "kPa", "MPa", "GPa"]),
+    unit("bar", "bars", Dimension::PRESSURE, BAR)
+        .symbols(&["bar"])
+        .si_prefixes(),
+    unit("barg", "barg", Dimension::PRESSURE, BAR).gauge(),
+    unit(
+        "atmosphere",
+        "atmospheres",
+        Dimension::PRESSURE,
+        STANDARD_ATMOSPHERE,
+    )
+    .symbols(&["atm"]),
+    unit(
+        "torr",
+        "torr",
+        Dimension::PRESSURE,
+        STANDARD_ATMOSPHERE / 760.0,
+    )
+    .symbols(&["Torr"])
+    .si_prefixes(),
+    unit(
+        "millimeter of mercury",
+        "millimeters of mercury",
+        Dimension::PRESSURE,
+        MMHG,
+    )
+    .symbols(&["mmHg"]),
+    unit(
+        "inch of mercury",
+        "inches of mercury",
+        Dimension::PRESSURE,
+        25.4 * MMHG,
+    )
+    .symbols(&["inHg"]),
+    // Conventional inch of water: an inch of water at 1000 kg/m³ under standard gravity
+    unit(
+        "inch of water",
+        "inches of water",
+        Dimension::PRESSURE,
+        INCH * 1000.0 * STANDARD_GRAVITY,
+    )
+    .aliases(&["inches water column", "iwc"])
+    .symbols(&["inH2O"]),
+    unit("psi", "psi", Dimension::PRESSURE, PSI)
+        .aliases(&["psia"])
+        .shown(System::Imperial, &["psi"]),
+    unit("psig", "psig", Dimension::PRESSURE, PSI).gauge(),
+    unit("ksi", "ksi", Dimension::PRESSURE, 1e3 * PSI).shown(System::Imperial, &["ksi"]),
+    // Flow and viscosity
+    // Metric flow rates (L/min, m³/h, kg/s) are compounds; these are the customary ones
+    // usually written as a single word
+    unit(
+        "gallon per minute",
+        "gallons per minute",
+        Dimension::VOLUMETRIC_FLOW,
+        GALLON / MINUTE,
+    )
+    .symbols(&["gpm", "GPM"])
+    .shown(System::Imperial, &["gpm"]),
+    unit(
+        "cubic foot per minute",
+        "cubic feet per minute",
+        Dimension::VOLUMETRIC_FLOW,
+        FOOT * FOOT * FOOT / MINUTE,
+    )
+    .symbols(&["cfm", "CFM"]),
+    // The CGS viscosity units, almost always seen as centipoise (cP) and centistokes (cSt)
+    unit("poise", "poise", Dimension::DYNAMIC_VISCOSITY, 0.1)
+        .symbols(&["P"])
+        .si_prefixes()
+        .shown(System::Metric, &["cP", "P"]),
+    unit("stokes", "stokes", Dimension::KINEMATIC_VISCOSITY, 1e-4)
+        .aliases(&["stoke"])
+        .symbols(&["St"])
+        .si_prefixes()
+        .shown(System::Metric, &["cSt", "St"]),
+    // Energy
+    unit("joule", "joules", Dimension::ENERGY, 1.0)
+        .symbols(&["J"])
+        .si_prefixes()
+        .shown(System::Metric, &["J", "kJ", "MJ", "GJ"]),
+    unit(
+        "foot pound",
+        "foot pounds",
+        Dimension::ENERGY,
+        FOOT * POUND * STANDARD_GRAVITY,
+    ),
+    unit("erg", "ergs", Dimension::ENERGY, 1e-7),
+    // Electricity bills: "kWh" is a kilowatt hour
+    unit("watt hour", "watt hours", Dimension::ENERGY, HOUR)
+        .symbols(&["Wh"])
+        .si_prefixes(),
+    // The thermochemical calorie (exactly 4.184 J) is the plain "calorie", as in
+    // chemistry and on US nutrition labels; "kcal" is a thousand of them
+    unit("calorie", "calories", Dimension::ENERGY, CALORIE)
+        .aliases(&["thermochemical calorie", "thermochemical calories"])
+        .symbols(&["cal", "cal_th"])
+        .si_prefixes(),
+    // The International Steam Table calorie, used in older engineering tables
+    unit("IT calorie", "IT calories", Dimension::ENERGY, IT_CALORIE)
+        .aliases(&["international calorie", "international calories"])
+        .symbols(&["cal_IT"]),
+    // The capitalized dietary Calorie is a thermochemical kilocalorie
+    unit(
+        "food calorie",
+        "food calories",
+        Dimension::ENERGY,
+        1e3 * CALORIE,
+    )
+    .aliases(&["dietary calorie", "dietary calories"])
+    .symbols(&["Cal"]),
+    // The International Table BTU is the usual one, for HVAC and gas
+    unit("BTU", "BTU", Dimension::ENERGY, BTU)
+        .aliases(&[
+            "british thermal unit",
+            "british thermal units",
+            "IT BTU",
+            "btus",
+        ])
+        .symbols(&["BTU", "Btu", "BTU_IT"])
+        .shown(System::Imperial, &["BTU"]),
+    unit(
+        "thermochemical BTU",
+        "thermochemical BTU",
+        Dimension::ENERGY,
+        1_054.350_264_488_9,
+    )
+    .symbols(&["BTU_th"]),
+    // The EC therm, 100,000 IT BTU; the US therm is a hair smaller
+    unit("therm", "therms", Dimension::ENERGY, 1e5 * BTU).symbols(&["thm"]),
+    unit("US therm", "US therms", Dimension::ENERGY, 105_480_400.0),
+    unit(
+        "electronvolt",
+        "electronvolts",
+        Dimension::ENERGY,
+        1.602_176_634e-19,
+    )
+    .aliases(&["electron volt", "electron volts"])
+    .symbols(&["eV"])
+    .si_prefixes(),
+    // By convention a ton of TNT releases exactly 10^9 thermochemical calories, so
+    // "kilotons of TNT" and "megatons of TNT" work through the prefixes
+    unit(
+        "ton of TNT",
+        "tons of TNT",
+        Dimension::ENERGY,
+        1e9 * CALORIE,
+    )
+    .aliases(&["tonne of tnt", "tonnes of tnt"])
+    .symbols(&["tTNT"])
+    .si_prefixes(),
+    // Power
+    unit("watt", "watts", Dimension::POWER, 1.0)
+        .symbols(&["W"])
+        .si_prefixes()
+        .shown(System::Metric, &["mW", "W", "kW", "MW", "GW"]),
+    // Mechanical (imperial) horsepower is 550 foot pounds per second, and is what a plain
+    // "hp" means
+    unit(
+        "horsepower",
+        "horsepower",
+        Dimension::POWER,
+        550.0 * FOOT * POUND * STANDARD_GRAVITY,
+    )
+    .aliases(&["mechanical horsepower", "imperial horsepower"])
+    .symbols(&["hp", "hp_I"])
+    .shown(System::Imperial, &["hp"]),
+    // Metric horsepower is 75 kilogram-force meters per second (PS in German, CV in
+    // French, Italian and Spanish)
+    unit(
+        "metric horsepower",
+        "metric horsepower",
+        Dimension::POWER,
+        75.0 * STANDARD_GRAVITY,
+    )
+    .aliases(&["pferdestärke", "cheval vapeur"])
+    .symbols(&["PS", "CV", "hp_M"]),
+    // Electrical horsepower, used for motor ratings, is exactly 746 watts
+    unit(
+        "electrical horsepower",
+        "electrical horsepower",
+        Dimension::POWER,
+        746.0,
+    )
+    .symbols(&["hp_E"]),
+    // A ton of refrigeration melts a short ton of ice a day: 12,000 BTU per hour
+    unit(
+        "ton of refrigeration",
+        "tons of refrigeration",
+        Dimension::POWER,
+        12_000.0 * BTU / HOUR,
+    )
+    .aliases(&["refrigeration ton", "refrigeration tons"])
+    .symbols(&["TR", "RT"]),
+    // Angle
+    unit("radian", "radians", Dimension::ANGLE, 1.0)
+        .symbols(&["rad"])
+        .si_prefixes(),
+    unit("degree", "degrees", Dimension::ANGLE, TURN / 360.0)
+        .aliases(&["degs"])
+        .symbols(&["°", "deg"])
+        .shown(System::Metric, &["°"]),
+    unit("gradian", "gradians", Dimension::ANGLE, TURN / 400.0)
+        .aliases(&["gon", "gons", "grads"])
+        .symbols(&["grad"]),
+    unit("turn", "turns", Dimension::ANGLE, TURN).aliases(&[
+        "revolution",
+        "revolutions",
+        "rev",
+        "revs",
+        "cycle",
+        "cycles",
+    ]),
+    unit(
+        "arcminute",
+        "arcminutes",
+        Dimension::ANGLE,
+        TURN / 360.0 / 60.0,
+    )
+    .aliases(&["arcmin", "minute of arc", "minutes of arc"])
+    .symbols(&["′"]),
+    unit(
+        "arcsecond",
+        "arcseconds",
+        Dimension::ANGLE,
+        TURN / 360.0 / 3600.0,
+    )
+    .aliases(&["arcsec", "second of arc", "seconds of arc"])
+    .symbols(&["″"]),
+    // The NATO mil, 1/6400 of a turn. A plain "mil" is a thousandth of an inch.
+    unit(
+        "angular mil",
+        "angular mils",
+        Dimension::ANGLE,
+        TURN / 6400.0,
+    )
+    .aliases(&["nato mil", "nato mils"]),
+    // Frequency and angular velocity
+    unit("hertz", "hertz", Dimension::FREQUENCY, 1.0)
+        .symbols(&["Hz"])
+        .si_prefixes()
+        .shown(System::Metric, &["Hz", "kHz", "MHz", "GHz", "THz"]),
+    unit("rpm", "rpm", Dimension::ANGULAR_VELOCITY, TURN / MINUTE).shown(System::Metric, &["rpm"]),
+    unit("rps", "rps", Dimension::ANGULAR_VELOCITY, TURN),
+    // Electricity and magnetism
+    unit("ampere", "amperes", Dimension::CURRENT, 1.0)
+        .aliases(&["amp", "amps"])
+        .symbols(&["A"])
+        .si_prefixes(),
+    unit("coulomb", "coulombs", Dimension::CHARGE, 1.0)
+        .symbols(&["C"])
+        .si_prefixes(),
+    // Battery capacity: "mAh" is a milliampere hour
+    unit("ampere hour", "ampere hours", Dimension::CHARGE, HOUR)
+        .aliases(&["amp hour", "amp hours"])
+        .symbols(&["Ah"])
+        .si_prefixes(),
+    unit("volt", "volts", Dimension::VOLTAGE, 1.0)
+        .symbols(&["V"])
+        .si_prefixes(),
+    // The ohm sign and the Greek capital omega look alike but are different characters
+    unit("ohm", "ohms", Dimension::RESISTANCE, 1.0)
+        .symbols(&["Ω", "Ω"])
+        .si_prefixes(),
+    unit("siemens", "siemens", Dimension::CONDUCTANCE, 1.0)
+        .aliases(&["mho", "mhos"])
+        .symbols(&["S"])
+        .si_prefixes(),
+    unit("farad", "farads", Dimension::CAPACITANCE, 1.0)
+        .symbols(&["F"])
+        .si_prefixes(),
+    unit("henry", "henries", Dimension::INDUCTANCE, 1.0)
+        .aliases(&["henrys"])
+        .symbols(&["H"])
+        .si_prefixes(),
+    unit("weber", "webers", Dimension::MAGNETIC_FLUX, 1.0)
+        .symbols(&["Wb"])
+        .si_prefixes(),
+    unit("maxwell", "maxwells", Dimension::MAGNETIC_FLUX, 1e-8).symbols(&["Mx"]),
+    unit("tesla", "teslas", Dimension::MAGNETIC_FLUX_DENSITY, 1.0)
+        .symbols(&["T"])
+        .si_prefixes(),
+    // No prefixes on the gauss: a kilogauss would be "kG", and "KG" has to stay a kilogram
+    unit("gauss", "gauss", Dimension::MAGNETIC_FLUX_DENSITY, 1e-4).symbols(&["G"]),
+    // Ratios and levels. A bare ratio is a power ratio, so `20 dB` is a ratio of 100.
+    // A neper is the natural log of an amplitude ratio, which makes it ln(10)/2 per
+    // tenfold change in power.
+    unit("ratio", "ratio", Dimension::NONE, 1.0)
+        .aliases(&["power ratio"])
+        .shown(System::Metric, &["ratio"]),
+    level("decibel", "decibels", Dimension::NONE, 1.0, 10.0).symbols(&["dB"]),
+    level(
+        "neper",
+        "nepers",
+        Dimension::NONE,
+        1.0,
+        std::f64::consts::LN_10 / 2.0,
+    )
+    .symbols(&["Np"]),
+    level(
+        "decibel-milliwatt",
+        "decibel-milliwatts",
+        Dimension::POWER,
+        1e-3,
+        10.0,
+    )
+    .symbols(&["dBm"]),
+    level("decibel-watt", "decibel-watts", Dimension::POWER, 1.0, 10.0).symbols(&["dBW"]),
+    level(
+        "decibel-volt",
+        "decibel-volts",
+        Dimension::VOLTAGE,
+        1.0,
+        20.0,
+    )
+    .symbols(&["dBV"]),
+    // 0 dBu is the voltage that puts a milliwatt into 600 ohms
+    level(
+        "decibel-unloaded",
+        "decibel-unloaded",
+        Dimension::VOLTAGE,
+        0.774_596_669_241_483_4,
+        20.0,
+    )
+    .symbols(&["dBu"]),
+    // Information
+    // "bit" doubles as a symbol so prefixed forms like "Mbit" work alongside "Mb"
+    unit("bit", "bits", Dimension::INFORMATION, 1.0)
+        .symbols(&["b", "bit"])
+        .data_prefixes(),
+    unit("nibble", "nibbles", Dimension::INFORMATION, 4.0).aliases(&["nybble", "nybbles"]),
+    unit("byte", "bytes", Dimension::INFORMATION, 8.0)
+        .aliases(&["octet", "octets"])
+        .symbols(&["B"])
+        .data_prefixes()
+        .shown(System::Metric, &["B", "kB", "MB", "GB", "TB", "PB"]),
+    // Data rate
+    unit(
+        "bit per second",
+        "bits per second",
+        Dimension::DATA_RATE,
+        1.0,
+    )
+    .symbols(&["bps"])
+    .data_prefixes()
+    .shown(System::Metric, &["bps", "kbps", "Mbps", "Gbps", "Tbps"]),
+    // Currency
+    // Each currency is worth whatever the loaded exchange rate table says. The ISO 4217
+    // code is the first symbol; a bare "$" or "¥" is the US dollar or the yen, and other
+    // dollars need their own prefix, as in "C$".
+    currency("US dollar", "US dollars", "USD")
+        .aliases(&["dollar", "dollars"])
+        .symbols(&["USD", "$", "US$"]),
+    currency("euro", "euros", "EUR").symbols(&["EUR", "€"]),
+    currency("British pound", "British pounds", "GBP")
+        .aliases(&["pound sterling", "pounds sterling", "sterling"])
+        .symbols(&["GBP", "£"]),
+    currency("Japanese yen", "Japanese yen", "JPY")
+        .aliases(&["yen"])
+        .symbols(&["JPY", "¥"]),
+    currency("Chinese yuan", "Chinese yuan", "CNY")
+        .aliases(&["yuan", "renminbi"])
+        .symbols(&["CNY", "CN¥"]),
+    currency("Swiss franc", "Swiss francs", "CHF").symbols(&["CHF"]),
+    currency("Canadian dollar", "Canadian dollars", "CAD").symbols(&["CAD", "C$", "CA$"]),
+    currency("Australian dollar", "Australian dollars", "AUD").symbols(&["AUD", "A$", "AU$"]),
+    currency("New Zealand dollar", "New Zealand dollars", "NZD").symbols(&["NZD", "NZ$"]),
+    currency("Hong Kong dollar", "Hong Kong dollars", "HKD").symbols(&["HKD", "HK$"]),
+    currency("Singapore dollar", "Singapore dollars", "SGD").symbols(&["SGD", "S$"]),
+    currency("Swedish krona", "Swedish kronor", "SEK").symbols(&["SEK"]),
+    currency("Norwegian krone", "Norwegian kroner", "NOK").symbols(&["NOK"]),
+    currency("Danish krone", "Danish kroner", "DKK").symbols(&["DKK"]),
+    currency("Polish zloty", "Polish zlotys", "PLN")
+        .aliases(&["złoty", "zloty", "zlotys"])
+        .symbols(&["PLN", "zł"]),
+    currency("Czech koruna", "Czech korunas", "CZK").symbols(&["CZK", "Kč"]),
+    currency("Hungarian forint", "Hungarian forints", "HUF")
+        .aliases(&["forint", "forints"])
+        .symbols(&["HUF"]),
+    currency("Indian rupee", "Indian rupees", "INR")
+        .aliases(&["rupee", "rupees"])
+        .symbols(&["INR", "₹"]),
+    currency("South Korean won", "South Korean won", "KRW")
+        .aliases(&["won"])
+        .symbols(&["KRW", "₩"]),
+    currency("Brazilian real", "Brazilian reais", "BRL").symbols(&["BRL", "R$"]),
+    currency("Mexican peso", "Mexican pesos", "MXN").symbols(&["MXN", "Mex$"]),
+    currency("South African rand", "South African rand", "ZAR")
+        .aliases(&["rand"])
+        .symbols(&["ZAR"]),
+    currency("Turkish lira", "Turkish lira", "TRY").symbols(&["TRY", "₺"]),
+    currency("Russian ruble", "Russian rubles", "RUB").symbols(&["RUB", "₽"]),
+    currency("Israeli new shekel", "Israeli new shekels", "ILS")
+        .aliases(&["shekel", "shekels"])
+        .symbols(&["ILS", "₪"]),
+    currency("Thai baht", "Thai baht", "THB")
+        .aliases(&["baht"])
+        .symbols(&["THB", "฿"]),
+    currency("Philippine peso", "Philippine pesos", "PHP").symbols(&["PHP", "₱"]),
+    currency("Ukrainian hryvnia", "Ukrainian hryvnias", "UAH").symbols(&["UAH", "₴"]),
+    currency("Nigerian naira", "Nigerian naira", "NGN").symbols(&["NGN", "₦"]),
+    currency("Vietnamese dong", "Vietnamese dong", "VND").symbols(&["VND", "₫"]),
+    currency("UAE dirham", "UAE dirhams", "AED").symbols(&["AED"]),
+    currency("Saudi riyal", "Saudi riyals", "SAR").symbols(&["SAR"]),
+];
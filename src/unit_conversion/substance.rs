@@ -0,0 +1,83 @@
+use super::{ConversionOptions, strip_suffix_ignoring_case};
+
+/// A substance that can be named after a quantity, as in `2 cups flour`, so its volume
+/// and mass convert into each other.
+#[derive(Debug)]
+struct Substance {
+    /// The first name is the one reported back
+    names: &'static [&'static str],
+    /// In kg/m³
+    density: f64,
+}
+
+const fn substance(names: &'static [&'static str], density: f64) -> Substance {
+    Substance { names, density }
+}
+
+// Kitchen ingredients use their bulk density as measured in a cup, spooned and levelled,
+// rather than the density of the solid itself: a cup of all-purpose flour weighs about
+// 125 g, far less than packed flour would. Liquids use their density at room temperature.
+static SUBSTANCES: &[Substance] = &[
+    // Liquids
+    substance(&["water"], 1000.0),
+    substance(&["milk"], 1030.0),
+    substance(&["cream", "heavy cream"], 1010.0),
+    substance(&["honey"], 1420.0),
+    substance(&["maple syrup"], 1330.0),
+    substance(&["olive oil"], 910.0),
+    substance(&["vegetable oil", "oil"], 920.0),
+    substance(&["seawater", "sea water"], 1025.0),
+    substance(&["gasoline", "petrol"], 740.0),
+    substance(&["diesel"], 840.0),
+    substance(&["ethanol", "alcohol"], 789.0),
+    substance(&["mercury"], 13_534.0),
+    // Dry ingredients
+    substance(&["flour", "all-purpose flour", "plain flour"], 530.0),
+    substance(&["bread flour"], 550.0),
+    substance(&["sugar", "granulated sugar", "white sugar"], 845.0),
+    substance(&["brown sugar"], 930.0),
+    substance(
+        &["powdered sugar", "icing sugar", "confectioners sugar"],
+        510.0,
+    ),
+    substance(&["salt", "table salt"], 1200.0),
+    substance(&["rice"], 845.0),
+    substance(&["rolled oats", "oats"], 380.0),
+    substance(&["cocoa powder", "cocoa"], 360.0),
+    // Fats
+    substance(&["butter"], 960.0),
+];
+
+/// Splits a trailing substance name off a quantity, as in `2 cups flour` or `1 liter of
+/// milk`, and returns the rest with the substance's name and density. Densities added
+/// through [`ConversionOptions::with_density`] take precedence over the built-in ones.
+pub(super) fn split<'a>(
+    input: &'a str,
+    options: &'a ConversionOptions,
+) -> Option<(&'a str, &'a str, f64)> {
+    let custom = options
+        .densities
+        .iter()
+        .map(|(name, density)| (name.as_str(), name.as_str(), *density));
+    let built_in = SUBSTANCES.iter().flat_map(|substance| {
+        substance
+            .names
+            .iter()
+            .map(|name| (*name, substance.names[0], substance.density))
+    });
+
+    let input = input.trim_end();
+    custom
+        .chain(built_in)
+        .filter_map(|(spelling, name, density)| {
+            let rest = strip_suffix_ignoring_case(input, spelling)?;
+            // The name has to be a word of its own, not the end of a unit like "oz"
+            let rest = rest.strip_suffix(char::is_whitespace)?.trim_end();
+            let rest = strip_suffix_ignoring_case(rest, " of").unwrap_or(rest);
+            Some((spelling.len(), (rest, name, density)))
+        })
+        // The longest name wins, so "brown sugar" beats "sugar", and on a tie the custom
+        // density comes first
+        .min_by_key(|(len, _)| std::cmp::Reverse(*len))
+        .map(|(_, found)| found)
+}